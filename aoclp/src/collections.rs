@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter::Sum;
+
+/// [Disjoint-set](https://en.wikipedia.org/wiki/Disjoint-set_data_structure) (aka union-find)
+/// structure, using path compression and union by size.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::collections::DisjointSet;
+///
+/// let mut set: DisjointSet<_> = (1..=5).collect();
+/// assert!(set.union(1, 2));
+/// assert!(set.union(3, 2));
+/// assert!(!set.union(1, 3));
+/// assert!(set.connected(&1, &3));
+/// assert_eq!(3, set.component_count());
+/// assert_eq!(3, set.component_size(&2));
+/// assert_eq!(vec![vec![1, 2, 3], vec![4], vec![5]], set.groups());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisjointSet<T> {
+    elements: Vec<T>,
+    indices: HashMap<T, usize>,
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    component_count: usize,
+}
+
+impl<T> DisjointSet<T>
+where
+    T: Clone + Eq + Hash,
+{
+    /// Creates a new, empty disjoint set.
+    pub fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+            indices: HashMap::new(),
+            parents: Vec::new(),
+            sizes: Vec::new(),
+            component_count: 0,
+        }
+    }
+
+    /// Inserts an element in its own component.
+    ///
+    /// Returns `false` if the element was already present.
+    pub fn insert(&mut self, element: T) -> bool {
+        let len = self.elements.len();
+        self.index_or_insert(element) == len
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns `true` if the set has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns `true` if the set contains the given element.
+    pub fn contains(&self, element: &T) -> bool {
+        self.indices.contains_key(element)
+    }
+
+    /// Returns the representative element of the component containing `element`,
+    /// or `None` if the element is not in the set.
+    pub fn find(&mut self, element: &T) -> Option<&T> {
+        let i = *self.indices.get(element)?;
+        let root = self.find_root(i);
+        Some(&self.elements[root])
+    }
+
+    /// Merges the components containing `a` and `b`, inserting them if needed.
+    ///
+    /// Returns `false` if `a` and `b` were already in the same component.
+    pub fn union(&mut self, a: T, b: T) -> bool {
+        let (a, b) = (self.index_or_insert(a), self.index_or_insert(b));
+        let (a, b) = (self.find_root(a), self.find_root(b));
+        if a == b {
+            return false;
+        }
+
+        let (big, small) = if self.sizes[a] >= self.sizes[b] { (a, b) } else { (b, a) };
+        self.parents[small] = big;
+        self.sizes[big] += self.sizes[small];
+        self.component_count -= 1;
+        true
+    }
+
+    /// Returns `true` if `a` and `b` are both in the set and in the same component.
+    pub fn connected(&mut self, a: &T, b: &T) -> bool {
+        match (self.indices.get(a).copied(), self.indices.get(b).copied()) {
+            (Some(a), Some(b)) => self.find_root(a) == self.find_root(b),
+            _ => false,
+        }
+    }
+
+    /// Returns the size of the component containing `element`, or 0 if the
+    /// element is not in the set.
+    pub fn component_size(&mut self, element: &T) -> usize {
+        match self.indices.get(element).copied() {
+            Some(i) => {
+                let root = self.find_root(i);
+                self.sizes[root]
+            },
+            None => 0,
+        }
+    }
+
+    /// Returns the number of components in the set.
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    /// Returns an [iterator](Iterator) of the sizes of all components in the set.
+    pub fn component_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(|(i, parent)| *i == **parent)
+            .map(|(i, _)| self.sizes[i])
+    }
+
+    /// Returns the elements of each component, grouped together.
+    ///
+    /// Components and elements within them are returned in insertion order.
+    pub fn groups(&self) -> Vec<Vec<T>> {
+        let mut group_indices = HashMap::new();
+        let mut groups: Vec<Vec<T>> = Vec::new();
+        for (i, element) in self.elements.iter().enumerate() {
+            let group = *group_indices.entry(self.root(i)).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(element.clone());
+        }
+        groups
+    }
+
+    fn index_or_insert(&mut self, element: T) -> usize {
+        *self.indices.entry(element).or_insert_with_key(|element| {
+            let i = self.elements.len();
+            self.elements.push(element.clone());
+            self.parents.push(i);
+            self.sizes.push(1);
+            self.component_count += 1;
+            i
+        })
+    }
+
+    fn root(&self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            i = self.parents[i];
+        }
+        i
+    }
+
+    fn find_root(&mut self, mut i: usize) -> usize {
+        let root = self.root(i);
+        while self.parents[i] != root {
+            i = std::mem::replace(&mut self.parents[i], root);
+        }
+        root
+    }
+}
+
+impl<T> Default for DisjointSet<T>
+where
+    T: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for DisjointSet<T>
+where
+    T: Clone + Eq + Hash,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for DisjointSet<T>
+where
+    T: Clone + Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// Output of [`kruskal`].
+#[derive(Debug, Clone)]
+pub struct SpanningForest<T, W> {
+    /// Edges that were used to connect components, in the order they were used.
+    pub edges: Vec<(T, T, W)>,
+    /// Components of the nodes after adding all `edges`.
+    pub set: DisjointSet<T>,
+}
+
+impl<T, W> SpanningForest<T, W>
+where
+    T: Clone + Eq + Hash,
+{
+    /// Returns `true` if all nodes ended up in a single component.
+    pub fn is_connected(&self) -> bool {
+        self.set.component_count() <= 1
+    }
+
+    /// Returns the edge that connected all nodes in a single component,
+    /// or `None` if nodes are not all connected.
+    pub fn connecting_edge(&self) -> Option<&(T, T, W)> {
+        self.edges.last().filter(|_| self.is_connected())
+    }
+
+    /// Returns the total weight of the forest's edges.
+    pub fn total_weight(&self) -> W
+    where
+        W: Sum<W> + Copy,
+    {
+        self.edges.iter().map(|(_, _, w)| *w).sum()
+    }
+}
+
+/// Builds a minimum spanning forest using
+/// [Kruskal's algorithm](https://en.wikipedia.org/wiki/Kruskal%27s_algorithm).
+///
+/// `edges` must be sorted by increasing weight. Stops as soon as all `nodes` are in a
+/// single component, so edges can be generated lazily.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::collections::kruskal;
+///
+/// let edges = [('a', 'b', 1), ('b', 'c', 2), ('a', 'c', 3), ('c', 'd', 4), ('a', 'd', 5)];
+/// let forest = kruskal("abcd".chars(), edges);
+/// assert!(forest.is_connected());
+/// assert_eq!(7, forest.total_weight());
+/// assert_eq!(Some(&('c', 'd', 4)), forest.connecting_edge());
+/// ```
+pub fn kruskal<T, W, N, E>(nodes: N, edges: E) -> SpanningForest<T, W>
+where
+    T: Clone + Eq + Hash,
+    N: IntoIterator<Item = T>,
+    E: IntoIterator<Item = (T, T, W)>,
+{
+    let mut set: DisjointSet<T> = nodes.into_iter().collect();
+    let mut forest_edges = Vec::new();
+
+    for (a, b, w) in edges {
+        if set.component_count() <= 1 {
+            break;
+        }
+        if set.union(a.clone(), b.clone()) {
+            forest_edges.push((a, b, w));
+        }
+    }
+
+    SpanningForest { edges: forest_edges, set }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut set = DisjointSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert("a"));
+        assert!(!set.insert("a"));
+        assert_eq!(Some(&"a"), set.find(&"a"));
+        assert_eq!(None, set.find(&"z"));
+
+        assert!(set.union("b", "c"));
+        assert!(set.union("d", "e"));
+        assert!(set.union("c", "e"));
+        assert_eq!(5, set.len());
+        assert_eq!(2, set.component_count());
+        assert_eq!(4, set.component_size(&"b"));
+        assert_eq!(0, set.component_size(&"z"));
+        assert_eq!(set.find(&"b").copied(), set.find(&"e").copied());
+        assert!(!set.connected(&"a", &"e"));
+        assert!(!set.connected(&"a", &"z"));
+
+        let mut sizes = set.component_sizes().collect::<Vec<_>>();
+        sizes.sort();
+        assert_eq!(vec![1, 4], sizes);
+        assert_eq!(vec![vec!["a"], vec!["b", "c", "d", "e"]], set.groups());
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let mut set: DisjointSet<_> = (0..10_000).collect();
+        for i in 1..10_000 {
+            set.union(i - 1, i);
+        }
+        assert_eq!(1, set.component_count());
+        assert!(set.connected(&0, &9_999));
+        assert_eq!(10_000, set.component_size(&5_000));
+    }
+
+    #[test]
+    fn test_kruskal() {
+        let forest = kruskal(1..=4, [(1, 2, 1), (3, 4, 1), (1, 2, 2)]);
+        assert!(!forest.is_connected());
+        assert_eq!(None, forest.connecting_edge());
+        assert_eq!(2, forest.total_weight());
+        assert_eq!(2, forest.set.component_count());
+    }
+}
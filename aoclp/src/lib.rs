@@ -1,11 +1,13 @@
 //! Helper library for Advent of Code.
 
 pub mod captures;
+pub mod collections;
 pub mod forth;
 pub mod functional;
 pub mod looping;
 pub mod mapping;
 pub mod positioning;
+pub mod search;
 pub mod solvers_impl;
 pub mod str;
 
@@ -1,2 +1,13 @@
+pub mod automaton;
+pub mod bitgrid;
 pub mod canvas;
 pub mod dij;
+pub mod dot;
+pub mod flow;
+pub mod graph;
+pub mod grid;
+pub mod packing;
+pub mod regions;
+pub mod search;
+pub mod sparse;
+pub mod tsp;
@@ -0,0 +1,389 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::iter::successors;
+use std::marker::PhantomData;
+
+use crate::mapping::canvas::dynamic::Canvas;
+use crate::mapping::sparse::SparseGrid;
+use crate::positioning::pt::Pt;
+
+/// Trait implemented by [cellular automata](https://en.wikipedia.org/wiki/Cellular_automaton),
+/// i.e. a local rule computing the next state of each cell from its current state and the
+/// state of its neighbours.
+///
+/// Cells are stored in a [`CellSpace`], which determines which cells need to be updated
+/// at each step. Stepping can be done one [generation](Self::generations) at a time, or
+/// directly to the [state after a number of steps](Self::run), using cycle detection.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::automaton::CellularAutomaton;
+/// # use aoclp::mapping::canvas::dynamic::Canvas;
+/// # use aoclp::mapping::grid::Neighbourhood;
+/// # use aoclp::positioning::pt::Pt;
+///
+/// // Every cell becomes the sum of its neighbours, modulo 10.
+/// struct Sum;
+///
+/// impl CellularAutomaton for Sum {
+///     type Pos = Pt;
+///     type Cell = u32;
+///
+///     fn neighbours(&self, pos: &Pt) -> impl Iterator<Item = Pt> {
+///         Neighbourhood::Four.of(*pos)
+///     }
+///
+///     fn rule<'a, I>(&self, _cell: &'a u32, neighbours: I) -> u32
+///     where
+///         I: Iterator<Item = &'a u32>,
+///     {
+///         neighbours.sum::<u32>() % 10
+///     }
+/// }
+///
+/// let canvas = Canvas::from_matrix([[1, 0, 0], [0, 0, 0]]);
+/// assert_eq!(vec![vec![0, 1, 0], vec![1, 0, 0]], Sum.step(&canvas).into_rows());
+/// assert_eq!(vec![vec![2, 0, 1], vec![0, 2, 0]], Sum.run(canvas, 2).into_rows());
+/// ```
+pub trait CellularAutomaton {
+    /// Type used to identify cells, like [`Pt`].
+    type Pos;
+
+    /// Type of cell states.
+    type Cell;
+
+    /// Returns the neighbours of the cell at `pos`.
+    fn neighbours(&self, pos: &Self::Pos) -> impl Iterator<Item = Self::Pos>;
+
+    /// Computes the next state of a cell, given its current state and the states of its
+    /// neighbours (as returned by [`neighbours`](Self::neighbours)).
+    ///
+    /// Depending on the [`CellSpace`], neighbours outside the space might be skipped.
+    fn rule<'a, I>(&self, cell: &'a Self::Cell, neighbours: I) -> Self::Cell
+    where
+        I: Iterator<Item = &'a Self::Cell>;
+
+    /// Computes the next generation of `space`.
+    fn step<S>(&self, space: &S) -> S
+    where
+        S: CellSpace<Self::Pos, Self::Cell>,
+        Self: Sized,
+    {
+        space.step(self)
+    }
+
+    /// Returns an [iterator](Iterator) of all successive generations of `space`, starting
+    /// with `space` itself.
+    fn generations<S>(&self, space: S) -> impl Iterator<Item = S>
+    where
+        S: CellSpace<Self::Pos, Self::Cell>,
+        Self: Sized,
+    {
+        successors(Some(space), |space| Some(self.step(space)))
+    }
+
+    /// Returns the state of `space` after the given number of `steps`.
+    ///
+    /// Generations are checked for cycles using [Brent's algorithm], so that the state can be
+    /// determined after a huge number of steps, provided the automaton eventually loops.
+    ///
+    /// [Brent's algorithm]: https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm
+    fn run<S>(&self, space: S, steps: usize) -> S
+    where
+        S: CellSpace<Self::Pos, Self::Cell> + Clone + PartialEq,
+        Self: Sized,
+    {
+        // Each generation is compared to a saved one; a new generation is saved whenever
+        // the distance between them reaches a power of two.
+        let mut space = space;
+        let mut saved = space.clone();
+        let (mut power, mut distance) = (1, 0);
+        for i in 1..=steps {
+            space = self.step(&space);
+            distance += 1;
+            if space == saved {
+                // Generations repeat every `distance` steps from here on
+                for _ in 0..(steps - i) % distance {
+                    space = self.step(&space);
+                }
+                return space;
+            }
+            if distance == power {
+                saved = space.clone();
+                power *= 2;
+                distance = 0;
+            }
+        }
+        space
+    }
+
+    /// Steps `space` until it no longer changes, returning the stable state and the
+    /// number of steps that changed it.
+    ///
+    /// # Notes
+    ///
+    /// Never returns if the automaton loops between different states.
+    fn run_until_stable<S>(&self, space: S) -> (S, usize)
+    where
+        S: CellSpace<Self::Pos, Self::Cell> + PartialEq,
+        Self: Sized,
+    {
+        let mut space = space;
+        let mut steps = 0;
+        loop {
+            let next = self.step(&space);
+            if next == space {
+                return (space, steps);
+            }
+            space = next;
+            steps += 1;
+        }
+    }
+}
+
+/// Trait implemented by containers of cells that can be stepped by a [`CellularAutomaton`].
+///
+/// The following spaces are supported:
+///
+/// - [`Canvas`]: every cell is updated; neighbours outside the canvas are skipped.
+/// - [`SparseGrid`]: cells that have been set and their neighbours are updated; other cells
+///   have the grid's default value, and cells equal to the default value are not stored.
+/// - [`HashSet`]: contains the positions of live cells (with a `bool` cell state); live cells
+///   and their neighbours are updated. Can be used for spaces of any dimension.
+pub trait CellSpace<P, C>: Sized {
+    /// Computes the next generation of this space using the given `automaton`.
+    fn step<A>(&self, automaton: &A) -> Self
+    where
+        A: CellularAutomaton<Pos = P, Cell = C>;
+}
+
+impl<C> CellSpace<Pt, C> for Canvas<C> {
+    fn step<A>(&self, automaton: &A) -> Self
+    where
+        A: CellularAutomaton<Pos = Pt, Cell = C>,
+    {
+        Canvas::from_matrix((0..self.height()).map(|y| {
+            (0..self.width()).map(move |x| {
+                let pos = Pt::new(x as i64, y as i64);
+                let neighbours = automaton.neighbours(&pos).filter_map(|n| self.get(n));
+                automaton.rule(&self[pos], neighbours)
+            })
+        }))
+    }
+}
+
+impl<C> CellSpace<Pt, C> for SparseGrid<C>
+where
+    C: Clone + PartialEq,
+{
+    fn step<A>(&self, automaton: &A) -> Self
+    where
+        A: CellularAutomaton<Pos = Pt, Cell = C>,
+    {
+        let mut candidates = HashSet::new();
+        for (pos, _) in self.iter() {
+            candidates.extend(automaton.neighbours(&pos));
+            candidates.insert(pos);
+        }
+
+        let mut next = SparseGrid::new(self.default_value().clone());
+        for pos in candidates {
+            let neighbours = automaton.neighbours(&pos).map(|n| self.get(n));
+            let cell = automaton.rule(self.get(pos), neighbours);
+            if cell != *self.default_value() {
+                next.insert(pos, cell);
+            }
+        }
+        next
+    }
+}
+
+impl<P> CellSpace<P, bool> for HashSet<P>
+where
+    P: Hash + Eq + Clone,
+{
+    fn step<A>(&self, automaton: &A) -> Self
+    where
+        A: CellularAutomaton<Pos = P, Cell = bool>,
+    {
+        let candidates: HashSet<P> = self
+            .iter()
+            .flat_map(|pos| automaton.neighbours(pos).chain([pos.clone()]))
+            .collect();
+
+        let alive = |pos: &P| if self.contains(pos) { &true } else { &false };
+        candidates
+            .into_iter()
+            .filter(|pos| {
+                let neighbours = automaton.neighbours(pos);
+                automaton.rule(alive(pos), neighbours.map(|n| alive(&n)))
+            })
+            .collect()
+    }
+}
+
+/// A [life-like](https://en.wikipedia.org/wiki/Life-like_cellular_automaton) cellular automaton,
+/// where dead cells are born and live cells survive depending on their number of live neighbours.
+///
+/// Neighbours are determined by a closure, so the automaton can run in spaces of any dimension.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use aoclp::mapping::automaton::{CellularAutomaton, LifeLike, moore_neighbours};
+///
+/// // Conway cubes, from Advent of Code 2020 day 17
+/// let life = LifeLike::new(|pos: &[i64; 3]| moore_neighbours(*pos), &[3], &[2, 3]);
+/// let cubes: HashSet<_> = [[1, 0, 0], [2, 1, 0], [0, 2, 0], [1, 2, 0], [2, 2, 0]].into();
+/// assert_eq!(112, life.run(cubes, 6).len());
+/// ```
+pub struct LifeLike<P, F> {
+    neighbours: F,
+    birth: u64,
+    survival: u64,
+    _pos: PhantomData<fn(&P)>,
+}
+
+impl<P, F> LifeLike<P, F> {
+    /// Creates a new life-like automaton where dead cells with a number of live neighbours
+    /// in `birth` become alive, and live cells with a number of live neighbours in `survival`
+    /// stay alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a number of neighbours is 64 or more.
+    pub fn new(neighbours: F, birth: &[usize], survival: &[usize]) -> Self {
+        let mask = |counts: &[usize]| {
+            counts.iter().fold(0u64, |mask, &count| {
+                assert!(count < 64, "unsupported number of neighbours: {count}");
+                mask | (1 << count)
+            })
+        };
+        Self { neighbours, birth: mask(birth), survival: mask(survival), _pos: PhantomData }
+    }
+
+    /// Creates an automaton following the rules of Conway's
+    /// [Game of Life](https://en.wikipedia.org/wiki/Conway%27s_Game_of_Life) (`B3/S23`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::automaton::{CellularAutomaton, LifeLike};
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    /// # use aoclp::mapping::grid::Neighbourhood;
+    /// # use aoclp::positioning::pt::Pt;
+    ///
+    /// let life = LifeLike::conway(|pt: &Pt| Neighbourhood::Eight.of(*pt));
+    /// let blinker = Canvas::from_lines(["...", "###", "..."], |c| c == '#');
+    /// assert_eq!(".#.\n.#.\n.#.", life.run(blinker, 1_000_000_001).to_string());
+    /// ```
+    pub fn conway(neighbours: F) -> Self {
+        Self::new(neighbours, &[3], &[2, 3])
+    }
+}
+
+impl<P, F, I> CellularAutomaton for LifeLike<P, F>
+where
+    F: Fn(&P) -> I,
+    I: IntoIterator<Item = P>,
+{
+    type Pos = P;
+    type Cell = bool;
+
+    fn neighbours(&self, pos: &P) -> impl Iterator<Item = P> {
+        (self.neighbours)(pos).into_iter()
+    }
+
+    fn rule<'a, J>(&self, cell: &'a bool, neighbours: J) -> bool
+    where
+        J: Iterator<Item = &'a bool>,
+    {
+        let alive = neighbours.filter(|n| **n).count();
+        let mask = if *cell { self.survival } else { self.birth };
+        alive < 64 && mask & (1 << alive) != 0
+    }
+}
+
+/// Returns the [Moore neighbourhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of
+/// a position in `D`-dimensional space, i.e. all positions that differ by at most one on
+/// each axis (excluding the position itself).
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::automaton::moore_neighbours;
+///
+/// assert_eq!(8, moore_neighbours([0, 0]).count());
+/// assert_eq!(80, moore_neighbours([1, 2, 3, 4]).count());
+/// assert!(moore_neighbours([0, 0, 0]).all(|n| n != [0, 0, 0]));
+/// ```
+pub fn moore_neighbours<const D: usize>(pos: [i64; D]) -> impl Iterator<Item = [i64; D]> {
+    let count = 3usize.pow(D as u32);
+    (0..count).filter(move |&i| i != count / 2).map(move |i| {
+        let mut neighbour = pos;
+        let mut i = i;
+        for coord in &mut neighbour {
+            *coord += (i % 3) as i64 - 1;
+            i /= 3;
+        }
+        neighbour
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::grid::Neighbourhood;
+
+    #[test]
+    fn test_glider() {
+        let life = LifeLike::conway(|pt: &Pt| Neighbourhood::Eight.of(*pt));
+        let glider: HashSet<Pt> = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .into_iter()
+            .map(Pt::from)
+            .collect();
+        let moved: HashSet<Pt> = glider.iter().map(|&pt| pt + Pt::new(5, 5)).collect();
+        assert_eq!(moved, life.run(glider.clone(), 20));
+
+        let grid: SparseGrid<bool> = glider.iter().map(|&pt| (pt, true)).collect();
+        let grid = life.run(grid, 20);
+        assert_eq!(moved, grid.iter().map(|(pt, _)| pt).collect());
+    }
+
+    #[test]
+    fn test_cycles() {
+        let life = LifeLike::conway(|pt: &Pt| Neighbourhood::Eight.of(*pt));
+        let blinker = Canvas::from_lines(["...", "###", "..."], |c| c == '#');
+        let flipped = Canvas::from_lines([".#.", ".#.", ".#."], |c| c == '#');
+        assert_eq!(blinker, life.run(blinker.clone(), 0));
+        assert_eq!(flipped, life.run(blinker.clone(), 1));
+        assert_eq!(blinker, life.run(blinker.clone(), 1_000_000_000_000));
+        assert_eq!(flipped, life.run(blinker.clone(), 1_000_000_000_001));
+
+        // Glider reaches the edge of the canvas and becomes a block after a few steps
+        let glider =
+            Canvas::from_lines([".#...", "..#..", "###..", ".....", "....."], |c| c == '#');
+        let expected = life.generations(glider.clone()).nth(50).unwrap();
+        assert_eq!(expected, life.run(glider.clone(), 50));
+        assert_eq!(expected, life.run(glider, usize::MAX));
+    }
+
+    #[test]
+    fn test_stable() {
+        let life = LifeLike::conway(|pt: &Pt| Neighbourhood::Eight.of(*pt));
+        let canvas = Canvas::from_lines(["....", ".##.", ".#..", "...."], |c| c == '#');
+        let (stable, steps) = life.run_until_stable(canvas);
+        assert_eq!(1, steps);
+        assert_eq!("....\n.##.\n.##.\n....", stable.to_string());
+    }
+
+    #[test]
+    fn test_4d() {
+        let life = LifeLike::conway(|pos: &[i64; 4]| moore_neighbours(*pos));
+        let cubes: HashSet<_> =
+            [[1, 0, 0, 0], [2, 1, 0, 0], [0, 2, 0, 0], [1, 2, 0, 0], [2, 2, 0, 0]].into();
+        assert_eq!(60, life.run(cubes, 2).len());
+    }
+}
@@ -0,0 +1,500 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use gratte::IntoEnumIterator;
+use num::ToPrimitive;
+
+use crate::mapping::canvas::{dynamic, fixed};
+use crate::mapping::grid::{GridCells, GridPoints, Neighbourhood};
+use crate::positioning::direction::MovementDirection;
+use crate::positioning::direction::eight_points::Direction8;
+use crate::positioning::direction::four_points::Direction4;
+use crate::positioning::pt::Pt;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A fixed-size 2D grid of booleans, stored as bits packed in `u64` words.
+///
+/// Each row is stored in its own set of words, so that whole-grid operations like
+/// [shifts](Self::shift), bitwise operations and [neighbour counts](Self::neighbour_counts)
+/// operate on 64 cells at a time.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::bitgrid::BitGrid;
+/// # use aoclp::positioning::direction::eight_points::Direction8;
+/// # use aoclp::positioning::pt::Pt;
+///
+/// let mut grid = BitGrid::from_lines(["#..", ".#.", "..."], |c| c == '#');
+/// grid.set(Pt::new(2, 2), true);
+/// assert_eq!(3, grid.count_ones());
+///
+/// let shifted = grid.shift(Direction8::BottomRight);
+/// assert_eq!("...\n.#.\n..#", shifted.to_string());
+/// assert_eq!(2, (&grid & &shifted).count_ones());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Creates a new grid of the given size where all cells are `false`.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(BITS);
+        Self { width, height, stride, words: vec![0; stride * height] }
+    }
+
+    /// Creates a new grid from lines of text, using a closure to convert every `char`
+    /// into a cell value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if lines are not all the same width.
+    pub fn from_lines<F, I, S>(lines: I, f: F) -> Self
+    where
+        F: FnMut(char) -> bool,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::from(&dynamic::Canvas::from_lines(lines, f))
+    }
+
+    /// Returns the grid's width.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the grid's height.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the value of the cell at the given [point](Pt), or `false` if the point is
+    /// outside the grid. Also works with coordinate tuples.
+    pub fn get<PT>(&self, pt: PT) -> bool
+    where
+        PT: Into<Pt>,
+    {
+        self.locate(pt.into())
+            .is_some_and(|(i, mask)| self.words[i] & mask != 0)
+    }
+
+    /// Sets the value of the cell at the given [point](Pt). Also works with coordinate tuples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point is outside the grid.
+    pub fn set<PT>(&mut self, pt: PT, value: bool)
+    where
+        PT: Into<Pt>,
+    {
+        let pt = pt.into();
+        let (i, mask) = self
+            .locate(pt)
+            .unwrap_or_else(|| panic!("position {pt} is outside the grid"));
+        if value {
+            self.words[i] |= mask;
+        } else {
+            self.words[i] &= !mask;
+        }
+    }
+
+    /// Returns the number of cells that are `true`.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if any cell is `true`.
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    /// Returns an [iterator](Iterator) of the points of all cells that are `true`,
+    /// in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = Pt> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, x0) = (i / self.stride, (i % self.stride) * BITS);
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let x = x0 + word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Pt::new(x as i64, y as i64)
+                })
+            })
+        })
+    }
+
+    /// Returns a copy of the grid where all cells have been moved one step in the given
+    /// direction. Cells moved outside the grid are dropped; cells left behind are `false`.
+    ///
+    /// Works with both [`Direction4`] and [`Direction8`].
+    pub fn shift<D>(&self, direction: D) -> Self
+    where
+        D: MovementDirection<i64>,
+    {
+        let displacement = direction.displacement();
+        self.shift_by(displacement.x, displacement.y)
+    }
+
+    /// Returns a copy of the grid where all cells have been moved by `dx` columns
+    /// and `dy` rows. Cells moved outside the grid are dropped; cells left behind are `false`.
+    pub fn shift_by(&self, dx: i64, dy: i64) -> Self {
+        let mut shifted = Self::new(self.width, self.height);
+        if self.stride == 0 {
+            return shifted;
+        }
+
+        for y in 0..self.height {
+            let Some(src_y) = (y as i64)
+                .checked_sub(dy)
+                .and_then(|src_y| src_y.to_usize())
+                .filter(|&src_y| src_y < self.height)
+            else {
+                continue;
+            };
+            shift_row(self.row(src_y), shifted.row_mut(y), dx);
+        }
+        shifted.clear_padding();
+        shifted
+    }
+
+    /// Counts the `true` neighbours of every cell of the grid, as determined by
+    /// the [`Neighbourhood`].
+    ///
+    /// Counts are computed for all cells at once using bitwise operations on whole
+    /// words, which makes this much faster than counting cell by cell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::bitgrid::BitGrid;
+    /// # use aoclp::mapping::grid::Neighbourhood;
+    ///
+    /// // Game of Life: a blinker oscillates between horizontal and vertical.
+    /// let grid = BitGrid::from_lines([".....", "..#..", "..#..", "..#..", "....."], |c| c == '#');
+    /// let counts = grid.neighbour_counts(Neighbourhood::Eight);
+    /// assert_eq!(3, counts.get((1, 2)));
+    ///
+    /// let next = &(&grid & &counts.equal_to(2)) | &counts.equal_to(3);
+    /// assert_eq!(".....\n.....\n.###.\n.....\n.....", next.to_string());
+    /// ```
+    pub fn neighbour_counts(&self, neighbourhood: Neighbourhood) -> NeighbourCounts {
+        let shifted: Vec<_> = match neighbourhood {
+            Neighbourhood::Four => Direction4::iter().map(|d| self.shift(d)).collect(),
+            Neighbourhood::Eight => Direction8::iter().map(|d| self.shift(d)).collect(),
+        };
+
+        let mut planes = [(); NeighbourCounts::PLANES].map(|_| Self::new(self.width, self.height));
+        for grid in shifted {
+            for (i, &word) in grid.words.iter().enumerate() {
+                let mut carry = word;
+                for plane in &mut planes {
+                    let bits = plane.words[i];
+                    plane.words[i] = bits ^ carry;
+                    carry &= bits;
+                }
+            }
+        }
+        NeighbourCounts { planes }
+    }
+
+    /// Converts the grid to a [`Canvas`](dynamic::Canvas).
+    pub fn to_canvas(&self) -> dynamic::Canvas<bool> {
+        dynamic::Canvas::from_matrix(
+            (0..self.height).map(|y| (0..self.width).map(move |x| self.get((x as i64, y as i64)))),
+        )
+    }
+
+    fn locate(&self, pt: Pt) -> Option<(usize, u64)> {
+        let (x, y) = (pt.x.to_usize()?, pt.y.to_usize()?);
+        (x < self.width && y < self.height).then(|| (y * self.stride + x / BITS, 1 << (x % BITS)))
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    fn clear_padding(&mut self) {
+        if !self.width.is_multiple_of(BITS) {
+            let mask = (1 << (self.width % BITS)) - 1;
+            self.words
+                .iter_mut()
+                .skip(self.stride - 1)
+                .step_by(self.stride)
+                .for_each(|w| *w &= mask);
+        }
+    }
+}
+
+fn shift_row(src: &[u64], dst: &mut [u64], dx: i64) {
+    let (words, bits) = ((dx.unsigned_abs() as usize) / BITS, (dx.unsigned_abs() as usize) % BITS);
+    for (i, word) in dst.iter_mut().enumerate() {
+        *word = if dx >= 0 {
+            let low = i.checked_sub(words + 1).map(|j| src[j]).unwrap_or(0);
+            let high = i.checked_sub(words).map(|j| src[j]).unwrap_or(0);
+            match bits {
+                0 => high,
+                _ => (high << bits) | (low >> (BITS - bits)),
+            }
+        } else {
+            let low = src.get(i + words).copied().unwrap_or(0);
+            let high = src.get(i + words + 1).copied().unwrap_or(0);
+            match bits {
+                0 => low,
+                _ => (low >> bits) | (high << (BITS - bits)),
+            }
+        };
+    }
+}
+
+/// Number of `true` neighbours of every cell of a [`BitGrid`], as returned by
+/// [`BitGrid::neighbour_counts`].
+///
+/// Counts are stored as bit planes, so that cells with a given count can be selected
+/// as a whole [`BitGrid`] at once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NeighbourCounts {
+    planes: [BitGrid; Self::PLANES],
+}
+
+impl NeighbourCounts {
+    const PLANES: usize = 4;
+    const MAX_COUNT: u8 = 8;
+
+    /// Returns the number of `true` neighbours of the cell at the given [point](Pt), or `0`
+    /// if the point is outside the grid. Also works with coordinate tuples.
+    pub fn get<PT>(&self, pt: PT) -> u8
+    where
+        PT: Into<Pt>,
+    {
+        let pt = pt.into();
+        self.planes
+            .iter()
+            .enumerate()
+            .map(|(bit, plane)| u8::from(plane.get(pt)) << bit)
+            .sum()
+    }
+
+    /// Returns a [`BitGrid`] where cells are `true` if they have exactly `count` `true` neighbours.
+    pub fn equal_to(&self, count: u8) -> BitGrid {
+        let empty = BitGrid::new(self.planes[0].width, self.planes[0].height);
+        if count > Self::MAX_COUNT {
+            return empty;
+        }
+
+        let mut grid = !&empty;
+        for (bit, plane) in self.planes.iter().enumerate() {
+            if count & (1 << bit) != 0 {
+                grid &= plane;
+            } else {
+                grid &= &!plane;
+            }
+        }
+        grid
+    }
+
+    /// Returns a [`BitGrid`] where cells are `true` if they have fewer than `count` `true` neighbours.
+    pub fn less_than(&self, count: u8) -> BitGrid {
+        (0..count.min(Self::MAX_COUNT + 1))
+            .fold(BitGrid::new(self.planes[0].width, self.planes[0].height), |grid, n| {
+                &grid | &self.equal_to(n)
+            })
+    }
+
+    /// Returns a [`BitGrid`] where cells are `true` if they have at least `count` `true` neighbours.
+    pub fn at_least(&self, count: u8) -> BitGrid {
+        !&self.less_than(count)
+    }
+}
+
+// A little helper macro to implement bitwise operators between grids.
+macro_rules! bitwise_op {
+    ($op:ident::$op_fn:ident, $assign_op:ident::$assign_fn:ident, $tok:tt) => {
+        impl $assign_op<&BitGrid> for BitGrid {
+            /// # Panics
+            ///
+            /// Panics if the grids are not the same size.
+            fn $assign_fn(&mut self, rhs: &BitGrid) {
+                assert_eq!(
+                    (self.width, self.height),
+                    (rhs.width, rhs.height),
+                    "grids must be the same size"
+                );
+                self.words
+                    .iter_mut()
+                    .zip(&rhs.words)
+                    .for_each(|(a, b)| *a = *a $tok *b);
+            }
+        }
+
+        impl $op<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            /// # Panics
+            ///
+            /// Panics if the grids are not the same size.
+            fn $op_fn(self, rhs: &BitGrid) -> Self::Output {
+                let mut grid = self.clone();
+                $assign_op::$assign_fn(&mut grid, rhs);
+                grid
+            }
+        }
+    };
+}
+
+bitwise_op!(BitAnd::bitand, BitAndAssign::bitand_assign, &);
+bitwise_op!(BitOr::bitor, BitOrAssign::bitor_assign, |);
+bitwise_op!(BitXor::bitxor, BitXorAssign::bitxor_assign, ^);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> Self::Output {
+        let mut grid = BitGrid { words: self.words.iter().map(|w| !w).collect(), ..*self };
+        grid.clear_padding();
+        grid
+    }
+}
+
+impl From<&dynamic::Canvas<bool>> for BitGrid {
+    fn from(value: &dynamic::Canvas<bool>) -> Self {
+        let mut grid = Self::new(value.width(), value.height());
+        for (y, row) in value.rows().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, b)| **b) {
+                grid.set((x as i64, y as i64), true);
+            }
+        }
+        grid
+    }
+}
+
+impl<const W: usize, const H: usize> From<&fixed::Canvas<bool, W, H>> for BitGrid {
+    fn from(value: &fixed::Canvas<bool, W, H>) -> Self {
+        let mut grid = Self::new(W, H);
+        for (y, row) in value.0.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, b)| **b) {
+                grid.set((x as i64, y as i64), true);
+            }
+        }
+        grid
+    }
+}
+
+impl GridCells<Pt> for BitGrid {
+    type Value = bool;
+
+    fn cell(&self, pt: &Pt) -> Option<&Self::Value> {
+        self.locate(*pt)
+            .map(|(i, mask)| if self.words[i] & mask != 0 { &true } else { &false })
+    }
+}
+
+impl GridPoints<Pt> for BitGrid {
+    fn points(&self) -> impl Iterator<Item = Pt> {
+        (0..self.height as i64)
+            .flat_map(move |y| (0..self.width as i64).map(move |x| Pt::new(x, y)))
+    }
+}
+
+impl Display for BitGrid {
+    /// Renders the grid as lines of text, with `#` for `true` cells and `.` for `false` cells.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y != 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                write!(f, "{}", if self.get((x as i64, y as i64)) { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn random_grid(width: usize, height: usize) -> BitGrid {
+        let mut state = 0x2545f4914f6cdd1du64;
+        let mut grid = BitGrid::new(width, height);
+        for pt in GridPoints::<Pt>::points(&grid.clone()) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            grid.set(pt, state.is_multiple_of(3));
+        }
+        grid
+    }
+
+    #[test]
+    fn test_shift() {
+        let grid = random_grid(150, 7);
+        let cells: HashSet<Pt> = grid.iter().collect();
+        assert_eq!(grid.count_ones(), cells.len());
+
+        for direction in Direction8::iter() {
+            let shifted = grid.shift(direction);
+            let expected: HashSet<Pt> = cells
+                .iter()
+                .map(|&pt| pt + direction.displacement())
+                .filter(|pt| pt.within(0..150, 0..7))
+                .collect();
+            assert_eq!(expected, shifted.iter().collect(), "shifting {direction}");
+        }
+
+        for dx in [-129, -64, -3, 0, 65, 149, 150] {
+            let shifted = grid.shift_by(dx, 1);
+            let expected: HashSet<Pt> = cells
+                .iter()
+                .map(|&pt| pt + Pt::new(dx, 1))
+                .filter(|pt| pt.within(0..150, 0..7))
+                .collect();
+            assert_eq!(expected, shifted.iter().collect(), "shifting by {dx}");
+        }
+    }
+
+    #[test]
+    fn test_neighbour_counts() {
+        let grid = random_grid(70, 9);
+        for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight] {
+            let counts = grid.neighbour_counts(neighbourhood);
+            for pt in grid.points() {
+                let expected = neighbourhood.of(pt).filter(|&n| grid.get(n)).count() as u8;
+                assert_eq!(expected, counts.get(pt));
+                assert_eq!(expected == 2, counts.equal_to(2).get(pt));
+                assert_eq!(expected < 3, counts.less_than(3).get(pt));
+                assert_eq!(expected >= 3, counts.at_least(3).get(pt));
+            }
+            assert!(!counts.equal_to(16).any());
+            assert_eq!(grid.width() * grid.height(), counts.less_than(u8::MAX).count_ones());
+            assert!(!counts.at_least(9).any());
+        }
+    }
+
+    #[test]
+    fn test_bitwise() {
+        let a = random_grid(65, 2);
+        let b = a.shift(Direction4::Right);
+        assert_eq!(
+            a.count_ones() + b.count_ones(),
+            (&a | &b).count_ones() + (&a & &b).count_ones()
+        );
+        assert_eq!((&a | &b).count_ones() - (&a & &b).count_ones(), (&a ^ &b).count_ones());
+        assert_eq!(130 - a.count_ones(), (!&a).count_ones());
+        assert_eq!(a, BitGrid::from(&a.to_canvas()));
+        assert!(!(&a & &!&a).any());
+    }
+}
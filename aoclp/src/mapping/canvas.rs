@@ -1,2 +1,18 @@
 pub mod dynamic;
 pub mod fixed;
+pub mod render;
+pub mod view;
+
+/// Trait implemented by canvas types that can be created from lines of text
+/// without panicking if the lines do not fit the canvas.
+pub trait TryFromLines<T>: Sized {
+    /// Tries to create a canvas from lines of text, using a closure to convert every `char`
+    /// into a piece of canvas.
+    ///
+    /// Returns an error if the lines do not form a valid canvas for this type.
+    fn try_from_lines<F, I, S>(lines: I, f: F) -> crate::Result<Self>
+    where
+        F: FnMut(char) -> T,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>;
+}
@@ -1,20 +1,29 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
-use std::iter::{repeat_n, successors};
+use std::iter::successors;
 use std::ops::{Index, IndexMut};
 use std::vec;
 
 use itertools::Itertools;
 use num::ToPrimitive;
 
+use crate::anyhow::anyhow;
+use crate::mapping::canvas::TryFromLines;
+use crate::mapping::canvas::render::{Glyph, Render};
+use crate::mapping::canvas::view::{CanvasView, Transform};
 use crate::positioning::Point;
 use crate::positioning::pt::{Pt, matrix_to_map};
 
 /// A fixed-size rectangular canvas in 2D space that can be rotated and flipped,
-/// stored in dynamically-allocated [`Vec`]s.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Canvas<T>(pub Vec<Vec<T>>);
+/// stored in a dynamically-allocated [`Vec`] in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Canvas<T> {
+    width: usize,
+    height: usize,
+    pieces: Vec<T>,
+}
 
 // noinspection DuplicatedCode
 impl<T> Canvas<T> {
@@ -30,21 +39,14 @@ impl<T> Canvas<T> {
     /// # use aoclp::mapping::canvas::dynamic::Canvas;
     ///
     /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
-    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.0);
+    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.into_rows());
     /// ```
     pub fn from_matrix<IR, IT>(matrix: IR) -> Self
     where
         IR: IntoIterator<Item = IT>,
         IT: IntoIterator<Item = T>,
     {
-        let canvas = Self(
-            matrix
-                .into_iter()
-                .map(|r| r.into_iter().collect())
-                .collect(),
-        );
-        canvas.validate_width();
-        canvas
+        Self::from_rows(matrix).unwrap_or_else(|| panic!("all rows need to have the same width"))
     }
 
     /// Creates a new [`Canvas`] from lines of text, using a closure to convert every `char`
@@ -63,7 +65,7 @@ impl<T> Canvas<T> {
     ///               456";
     ///
     /// let canvas = Canvas::from_lines(matrix.lines(), |c| c.to_digit(10).unwrap());
-    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.0);
+    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.into_rows());
     /// ```
     pub fn from_lines<F, I, S>(lines: I, mut f: F) -> Self
     where
@@ -71,14 +73,11 @@ impl<T> Canvas<T> {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let canvas = Self(
+        Self::from_matrix(
             lines
                 .into_iter()
-                .map(move |s| s.as_ref().chars().map(&mut f).collect())
-                .collect(),
-        );
-        canvas.validate_width();
-        canvas
+                .map(move |s| s.as_ref().chars().map(&mut f).collect_vec()),
+        )
     }
 
     /// Creates a new [`Canvas`] from lines of text, using a closure to convert every byte
@@ -97,7 +96,7 @@ impl<T> Canvas<T> {
     ///               456";
     ///
     /// let canvas = Canvas::from_line_bytes(matrix.lines(), |c| c - b'0');
-    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.0);
+    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.into_rows());
     /// ```
     pub fn from_line_bytes<F, I, S>(lines: I, mut f: F) -> Self
     where
@@ -105,14 +104,11 @@ impl<T> Canvas<T> {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let canvas = Self(
+        Self::from_matrix(
             lines
                 .into_iter()
-                .map(move |s| s.as_ref().bytes().map(&mut f).collect())
-                .collect(),
-        );
-        canvas.validate_width();
-        canvas
+                .map(move |s| s.as_ref().bytes().map(&mut f).collect_vec()),
+        )
     }
 
     /// Returns the width of the canvas.
@@ -126,7 +122,7 @@ impl<T> Canvas<T> {
     /// assert_eq!(3, canvas.width());
     /// ```
     pub fn width(&self) -> usize {
-        self.0.first().map_or(0, Vec::len)
+        self.width
     }
 
     /// Returns the height of the canvas.
@@ -140,7 +136,7 @@ impl<T> Canvas<T> {
     /// assert_eq!(2, canvas.height());
     /// ```
     pub fn height(&self) -> usize {
-        self.0.len()
+        self.height
     }
 
     /// Returns the number of pieces in the canvas ([width] multiplied by [height]).
@@ -182,7 +178,271 @@ impl<T> Canvas<T> {
     /// );
     /// ```
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T>> {
-        self.0.iter().map(|r| r.iter())
+        self.rows().map(|r| r.iter())
+    }
+
+    /// Returns an [iterator] of the canvas' rows, as slices.
+    ///
+    /// [iterator]: DoubleEndedIterator
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// let rows: Vec<&[i32]> = canvas.rows().collect();
+    /// assert_eq!(vec![&[1, 2, 3], &[4, 5, 6]], rows);
+    /// ```
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        (0..self.height).map(|y| &self.pieces[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Returns the row at index `y` as a slice, or `None` if `y` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(Some(&[4, 5, 6][..]), canvas.row(1));
+    /// assert_eq!(None, canvas.row(2));
+    /// ```
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.pieces[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Returns the row at index `y` as a mutable slice, or `None` if `y` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let mut canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// canvas.row_mut(0).unwrap().fill(0);
+    /// assert_eq!(vec![vec![0, 0, 0], vec![4, 5, 6]], canvas.into_rows());
+    /// ```
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [T]> {
+        (y < self.height).then(|| &mut self.pieces[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Returns all canvas pieces as a slice, in row-major order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(&[1, 2, 3, 4, 5, 6], canvas.as_slice());
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        &self.pieces
+    }
+
+    /// Returns a reference to the canvas piece at the given [point](Pt), or `None` if the
+    /// point is outside the canvas. Also works with coordinate tuples.
+    ///
+    /// The top-left canvas piece is at position `(0, 0)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    /// # use aoclp::positioning::pt::Pt;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(Some(&5), canvas.get(Pt::new(1, 1)));
+    /// assert_eq!(None, canvas.get((3, 0)));
+    /// assert_eq!(None, canvas.get((-1, 0)));
+    /// ```
+    pub fn get<PT>(&self, pt: PT) -> Option<&T>
+    where
+        PT: Into<Pt>,
+    {
+        self.offset(pt.into()).map(|i| &self.pieces[i])
+    }
+
+    /// Returns a mutable reference to the canvas piece at the given [point](Pt), or `None`
+    /// if the point is outside the canvas. Also works with coordinate tuples.
+    ///
+    /// The top-left canvas piece is at position `(0, 0)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let mut canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// *canvas.get_mut((2, 0)).unwrap() = 7;
+    /// assert!(canvas.get_mut((0, 2)).is_none());
+    /// assert_eq!(vec![vec![1, 2, 7], vec![4, 5, 6]], canvas.into_rows());
+    /// ```
+    pub fn get_mut<PT>(&mut self, pt: PT) -> Option<&mut T>
+    where
+        PT: Into<Pt>,
+    {
+        self.offset(pt.into()).map(|i| &mut self.pieces[i])
+    }
+
+    /// Returns a reference to the canvas piece at the given [point](Pt), [wrapping](Pt::wrap)
+    /// the point around the canvas edges, as if the canvas was repeated infinitely in all
+    /// directions. Also works with coordinate tuples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(&1, canvas.get_wrapping((3, 2)));
+    /// assert_eq!(&6, canvas.get_wrapping((-1, -1)));
+    /// ```
+    pub fn get_wrapping<PT>(&self, pt: PT) -> &T
+    where
+        PT: Into<Pt>,
+    {
+        &self.pieces[self.wrapping_offset(pt.into())]
+    }
+
+    /// Returns a mutable reference to the canvas piece at the given [point](Pt),
+    /// [wrapping](Pt::wrap) the point around the canvas edges. Also works with coordinate tuples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let mut canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// *canvas.get_wrapping_mut((-4, 5)) = 7;
+    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 7]], canvas.into_rows());
+    /// ```
+    pub fn get_wrapping_mut<PT>(&mut self, pt: PT) -> &mut T
+    where
+        PT: Into<Pt>,
+    {
+        let offset = self.wrapping_offset(pt.into());
+        &mut self.pieces[offset]
+    }
+
+    /// Converts the canvas into a two-dimensional matrix of pieces. This is the inverse
+    /// of [`from_matrix`](Self::from_matrix).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.into_rows());
+    /// ```
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        let mut pieces = self.pieces.into_iter();
+        (0..self.height)
+            .map(|_| pieces.by_ref().take(self.width).collect())
+            .collect()
+    }
+
+    /// Returns a [view](CanvasView) of this canvas with the given `transform` applied,
+    /// without copying the canvas pieces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    /// # use aoclp::mapping::canvas::view::Transform;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// let view = canvas.view(Transform::Transpose);
+    /// assert_eq!(vec![vec![1, 4], vec![2, 5], vec![3, 6]], view.to_canvas().into_rows());
+    /// ```
+    pub fn view(&self, transform: Transform) -> CanvasView<'_, T> {
+        CanvasView::new(self, transform)
+    }
+
+    /// Returns an [iterator](Iterator) of [views](CanvasView) of this canvas for all
+    /// eight possible [transforms](Transform::ALL).
+    ///
+    /// Unlike [`into_variations`](Self::into_variations), this does not copy the canvas
+    /// pieces and does not require the canvas to be square.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    /// # use itertools::Itertools;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(8, canvas.views().count());
+    /// assert_eq!(8, canvas.views().map(|view| view.to_canvas()).unique().count());
+    ///
+    /// let square = Canvas::from_matrix([[1, 2], [3, 4]]);
+    /// let views = square
+    ///     .views()
+    ///     .map(|view| view.to_canvas())
+    ///     .sorted()
+    ///     .collect_vec();
+    /// assert_eq!(
+    ///     square
+    ///         .clone()
+    ///         .into_variations()
+    ///         .sorted()
+    ///         .dedup()
+    ///         .collect_vec(),
+    ///     views
+    /// );
+    /// ```
+    pub fn views(&self) -> impl Iterator<Item = CanvasView<'_, T>> {
+        Transform::ALL
+            .into_iter()
+            .map(|transform| self.view(transform))
+    }
+
+    /// Returns a [`Render`] of this canvas, using the pieces' [`Glyph`] to render them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    /// # use aoclp::positioning::pt::Pt;
+    ///
+    /// let canvas = Canvas::from_lines(["..#", "#.."], |c| c);
+    /// let render = canvas.render().mark(Pt::new(1, 0), '@');
+    /// assert_eq!(".@#\n#..", render.to_string());
+    /// ```
+    pub fn render(&self) -> Render<'_, T>
+    where
+        T: Glyph,
+    {
+        self.render_with(T::glyph)
+    }
+
+    /// Returns a [`Render`] of this canvas, using a closure to convert every piece to a `char`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// let render = canvas.render_with(|n| char::from_digit(*n, 10).unwrap());
+    /// assert_eq!("123\n456", render.to_string());
+    /// ```
+    pub fn render_with<'a, F>(&'a self, glyph: F) -> Render<'a, T>
+    where
+        F: Fn(&T) -> char + 'a,
+    {
+        Render::new(&self.pieces, self.width, self.height, glyph)
     }
 
     /// Flips the canvas horizontally.
@@ -193,10 +453,16 @@ impl<T> Canvas<T> {
     /// # use aoclp::mapping::canvas::dynamic::Canvas;
     ///
     /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
-    /// assert_eq!(vec![vec![4, 5, 6], vec![1, 2, 3]], canvas.flip_horizontally().0);
+    /// assert_eq!(vec![vec![4, 5, 6], vec![1, 2, 3]], canvas.flip_horizontally().into_rows());
     /// ```
-    pub fn flip_horizontally(self) -> Self {
-        Self(self.0.into_iter().rev().collect())
+    pub fn flip_horizontally(mut self) -> Self {
+        for y in 0..self.height / 2 {
+            for x in 0..self.width {
+                self.pieces
+                    .swap(y * self.width + x, (self.height - 1 - y) * self.width + x);
+            }
+        }
+        self
     }
 
     /// Flips this canvas vertically.
@@ -207,15 +473,98 @@ impl<T> Canvas<T> {
     /// # use aoclp::mapping::canvas::dynamic::Canvas;
     ///
     /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
-    /// assert_eq!(vec![vec![3, 2, 1], vec![6, 5, 4]], canvas.flip_vertically().0);
+    /// assert_eq!(vec![vec![3, 2, 1], vec![6, 5, 4]], canvas.flip_vertically().into_rows());
     /// ```
-    pub fn flip_vertically(self) -> Self {
-        Self(
-            self.0
-                .into_iter()
-                .map(|l| l.into_iter().rev().collect())
-                .collect(),
-        )
+    pub fn flip_vertically(mut self) -> Self {
+        for y in 0..self.height {
+            self.row_mut(y).unwrap().reverse();
+        }
+        self
+    }
+
+    /// Transposes this canvas, so that its rows become its columns.
+    ///
+    /// The transposition is performed in place, without allocating a new canvas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(vec![vec![1, 4], vec![2, 5], vec![3, 6]], canvas.transpose().into_rows());
+    /// ```
+    pub fn transpose(mut self) -> Self {
+        // Piece at offset `i` moves to offset `target(i)`; follow each cycle of this
+        // permutation from its smallest offset (its leader), using it as a temporary slot.
+        let (width, height) = (self.width, self.height);
+        let target = |i: usize| (i % width) * height + i / width;
+        for start in 0..self.pieces.len() {
+            let mut i = target(start);
+            while i > start {
+                i = target(i);
+            }
+            if i < start {
+                continue;
+            }
+
+            let mut i = target(start);
+            while i != start {
+                self.pieces.swap(start, i);
+                i = target(i);
+            }
+        }
+
+        (self.width, self.height) = (height, width);
+        self
+    }
+
+    /// Rotates this canvas 90 degrees to the left.
+    ///
+    /// The rotation is performed in place, without allocating a new canvas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(vec![vec![3, 6], vec![2, 5], vec![1, 4]], canvas.rotate_left().into_rows());
+    /// ```
+    pub fn rotate_left(self) -> Self {
+        self.transpose().flip_horizontally()
+    }
+
+    /// Rotates this canvas 90 degrees to the right.
+    ///
+    /// The rotation is performed in place, without allocating a new canvas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(vec![vec![4, 1], vec![5, 2], vec![6, 3]], canvas.rotate_right().into_rows());
+    /// ```
+    pub fn rotate_right(self) -> Self {
+        self.transpose().flip_vertically()
+    }
+
+    /// Converts the canvas into an [iterator] of its columns, from left to right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(vec![vec![1, 4], vec![2, 5], vec![3, 6]], canvas.into_columns().collect::<Vec<_>>());
+    /// ```
+    ///
+    /// [iterator]: DoubleEndedIterator
+    pub fn into_columns(self) -> impl DoubleEndedIterator<Item = Vec<T>> {
+        self.transpose().into_rows().into_iter()
     }
 
     /// Converts the canvas into a [map](HashMap) associating each canvas piece
@@ -250,72 +599,251 @@ impl<T> Canvas<T> {
         <PT as TryFrom<usize>>::Error: Debug,
         Pt<PT>: Copy + Hash + Eq,
     {
-        matrix_to_map(self.0)
+        matrix_to_map(self)
     }
 
-    fn validate_width(&self) {
-        if let Some(row) = self.0.first()
-            && self.0.iter().any(|r| r.len() != row.len())
-        {
-            panic!("all rows need to have the same width");
+    /// Converts every piece of the canvas using a closure, returning a new [`Canvas`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(vec![vec![2, 4, 6], vec![8, 10, 12]], canvas.map(|p| p * 2).into_rows());
+    /// ```
+    pub fn map<U, F>(self, f: F) -> Canvas<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Canvas {
+            width: self.width,
+            height: self.height,
+            pieces: self.pieces.into_iter().map(f).collect(),
         }
     }
-}
 
-// noinspection DuplicatedCode
-impl<T> Canvas<T>
-where
-    T: Clone,
-{
-    /// Creates a new [`Canvas`] with every piece set to the same `value`.
+    /// Splits the canvas into tiles of `tile_width` by `tile_height` pieces, returning
+    /// a canvas of tiles. The top-left tile is at position `(0, 0)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas cannot be split evenly into tiles of the given size.
     ///
     /// # Example
     ///
     /// ```
     /// # use aoclp::mapping::canvas::dynamic::Canvas;
     ///
-    /// let canvas = Canvas::of(3, 2, '#');
-    /// assert_eq!(vec![vec!['#', '#', '#'], vec!['#', '#', '#']], canvas.0);
+    /// let canvas = Canvas::from_matrix([[1, 2, 3, 4], [5, 6, 7, 8]]);
+    /// let tiles = canvas.split_into_tiles(2, 2);
+    /// assert_eq!(2, tiles.width());
+    /// assert_eq!(1, tiles.height());
+    /// assert_eq!(Canvas::from_matrix([[3, 4], [7, 8]]), tiles[(1, 0)]);
     /// ```
-    pub fn of(width: usize, height: usize, value: T) -> Self {
-        Self(
-            (0..height)
-                .map(move |_| repeat_n(value.clone(), width).collect())
+    pub fn split_into_tiles(self, tile_width: usize, tile_height: usize) -> Canvas<Canvas<T>> {
+        if tile_width == 0
+            || tile_height == 0
+            || !self.width().is_multiple_of(tile_width)
+            || !self.height().is_multiple_of(tile_height)
+        {
+            panic!(
+                "cannot split {}x{} canvas into {tile_width}x{tile_height} tiles",
+                self.width(),
+                self.height()
+            );
+        }
+
+        let tiles_per_row = self.width / tile_width;
+        let tile_rows = self.height / tile_height;
+        let mut tiles: Vec<Vec<T>> = (0..tiles_per_row * tile_rows).map(|_| Vec::new()).collect();
+        for (i, piece) in self.pieces.into_iter().enumerate() {
+            let (x, y) = (i % self.width, i / self.width);
+            tiles[(y / tile_height) * tiles_per_row + x / tile_width].push(piece);
+        }
+
+        Canvas {
+            width: tiles_per_row,
+            height: tile_rows,
+            pieces: tiles
+                .into_iter()
+                .map(|pieces| Self { width: tile_width, height: tile_height, pieces })
                 .collect(),
-        )
+        }
     }
 
-    /// Rotates this canvas 90 degrees to the left.
+    /// Assembles a canvas of tiles into one large [`Canvas`]. This is the inverse
+    /// of [`split_into_tiles`](Self::split_into_tiles).
     ///
-    /// # Examples
+    /// Tiles do not need to all be the same size, but tiles on the same row need to be
+    /// the same height and tiles on the same column need to be the same width.
+    ///
+    /// # Panics
+    ///
+    /// Panics if tiles do not line up.
+    ///
+    /// # Example
     ///
     /// ```
     /// # use aoclp::mapping::canvas::dynamic::Canvas;
     ///
-    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
-    /// assert_eq!(vec![vec![3, 6], vec![2, 5], vec![1, 4]], canvas.rotate_left().0);
+    /// let tiles = Canvas::from_matrix([[
+    ///     Canvas::from_matrix([[1], [4]]),
+    ///     Canvas::from_matrix([[2, 3], [5, 6]]),
+    /// ]]);
+    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], Canvas::from_tiles(tiles).into_rows());
     /// ```
-    pub fn rotate_left(self) -> Self {
-        Self(self.into_columns().rev().collect())
+    pub fn from_tiles(tiles: Canvas<Canvas<T>>) -> Self {
+        Self::from_matrix(tiles.into_rows().into_iter().flat_map(|tile_row| {
+            let height = tile_row.first().map_or(0, Canvas::height);
+            if tile_row.iter().any(|tile| tile.height() != height) {
+                panic!("all tiles on the same row need to have the same height");
+            }
+
+            let mut tile_rows = tile_row
+                .into_iter()
+                .map(|tile| tile.into_rows().into_iter())
+                .collect_vec();
+            (0..height).map(move |_| {
+                tile_rows
+                    .iter_mut()
+                    .flat_map(|rows| rows.next().unwrap())
+                    .collect_vec()
+            })
+        }))
     }
 
-    /// Converts the canvas into an [iterator] of its columns, from left to right.
+    /// Assembles a canvas of tiles into one large [`Canvas`] like [`from_tiles`](Self::from_tiles),
+    /// but first [trims](Self::trim_border) `border` pieces off each side of every tile.
+    ///
+    /// Useful for jigsaw-style puzzles, where tiles have borders that are only used to
+    /// figure out how they fit together.
     ///
     /// # Example
     ///
     /// ```
     /// # use aoclp::mapping::canvas::dynamic::Canvas;
     ///
-    /// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
-    /// assert_eq!(vec![vec![1, 4], vec![2, 5], vec![3, 6]], canvas.into_columns().collect::<Vec<_>>());
+    /// let tile = Canvas::from_lines(["###", "#.#", "###"], |c| c);
+    /// let tiles = Canvas::of(2, 1, tile);
+    /// assert_eq!(vec![vec!['.', '.']], Canvas::from_trimmed_tiles(tiles, 1).into_rows());
     /// ```
+    pub fn from_trimmed_tiles(tiles: Canvas<Canvas<T>>, border: usize) -> Self {
+        Self::from_tiles(tiles.map(|tile| tile.trim_border(border)))
+    }
+
+    /// Removes `border` pieces from each side of the canvas.
     ///
-    /// [iterator]: DoubleEndedIterator
-    pub fn into_columns(self) -> impl DoubleEndedIterator<Item = Vec<T>> {
-        (0..self.width())
-            .map(|col| self.0.iter().map(|r| r[col].clone()).collect_vec())
-            .collect_vec()
+    /// # Panics
+    ///
+    /// Panics if the canvas is too small to remove the border.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]);
+    /// assert_eq!(vec![vec![6, 7]], canvas.trim_border(1).into_rows());
+    /// ```
+    pub fn trim_border(self, border: usize) -> Self {
+        let (width, height) = (self.width(), self.height());
+        if width < border * 2 || height < border * 2 {
+            panic!("cannot trim border of {border} from {width}x{height} canvas");
+        }
+
+        let pieces = self
+            .pieces
             .into_iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                let (x, y) = (i % width, i / width);
+                (border..width - border).contains(&x) && (border..height - border).contains(&y)
+            })
+            .map(|(_, piece)| piece)
+            .collect();
+        Self { width: width - border * 2, height: height - border * 2, pieces }
+    }
+
+    /// Creates a canvas from rows of pieces, or returns `None` if rows are not all the same width.
+    fn from_rows<IR, IT>(rows: IR) -> Option<Self>
+    where
+        IR: IntoIterator<Item = IT>,
+        IT: IntoIterator<Item = T>,
+    {
+        let (mut width, mut height, mut pieces) = (None, 0, Vec::new());
+        for row in rows {
+            let start = pieces.len();
+            pieces.extend(row);
+            if *width.get_or_insert(pieces.len() - start) != pieces.len() - start {
+                return None;
+            }
+            height += 1;
+        }
+        Some(Self { width: width.unwrap_or(0), height, pieces })
+    }
+
+    fn offset(&self, pt: Pt) -> Option<usize> {
+        let (x, y) = (pt.x.to_usize()?, pt.y.to_usize()?);
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    fn wrapping_offset(&self, pt: Pt) -> usize {
+        assert!(self.count() != 0, "cannot wrap around an empty canvas");
+        self.offset(pt.wrap(self.width as i64, self.height as i64))
+            .unwrap()
+    }
+}
+
+impl<T> TryFromLines<T> for Canvas<T> {
+    /// Tries to create a new [`Canvas`] from lines of text, using a closure to convert every
+    /// `char` into a piece of canvas.
+    ///
+    /// Returns an error if lines are not all the same width.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::TryFromLines;
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::try_from_lines(["123", "456"], |c| c.to_digit(10).unwrap()).unwrap();
+    /// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], canvas.into_rows());
+    ///
+    /// assert!(Canvas::try_from_lines(["123", "45"], |c| c).is_err());
+    /// ```
+    fn try_from_lines<F, I, S>(lines: I, mut f: F) -> crate::Result<Self>
+    where
+        F: FnMut(char) -> T,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::from_rows(
+            lines
+                .into_iter()
+                .map(|s| s.as_ref().chars().map(&mut f).collect_vec()),
+        )
+        .ok_or_else(|| anyhow!("all rows need to have the same width"))
+    }
+}
+
+// noinspection DuplicatedCode
+impl<T> Canvas<T>
+where
+    T: Clone,
+{
+    /// Creates a new [`Canvas`] with every piece set to the same `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::of(3, 2, '#');
+    /// assert_eq!(vec![vec!['#', '#', '#'], vec!['#', '#', '#']], canvas.into_rows());
+    /// ```
+    pub fn of(width: usize, height: usize, value: T) -> Self {
+        Self { width, height, pieces: vec![value; width * height] }
     }
 
     /// Consumes the canvas and returns an [iterator](Iterator) of all possible 90-degrees
@@ -336,10 +864,10 @@ where
     /// let canvas = Canvas::from_matrix([[1, 2], [3, 4]]);
     ///
     /// let mut rotations = canvas.into_rotations();
-    /// assert_eq!(vec![vec![1, 2], vec![3, 4]], rotations.next().unwrap().0);
-    /// assert_eq!(vec![vec![2, 4], vec![1, 3]], rotations.next().unwrap().0);
-    /// assert_eq!(vec![vec![4, 3], vec![2, 1]], rotations.next().unwrap().0);
-    /// assert_eq!(vec![vec![3, 1], vec![4, 2]], rotations.next().unwrap().0);
+    /// assert_eq!(vec![vec![1, 2], vec![3, 4]], rotations.next().unwrap().into_rows());
+    /// assert_eq!(vec![vec![2, 4], vec![1, 3]], rotations.next().unwrap().into_rows());
+    /// assert_eq!(vec![vec![4, 3], vec![2, 1]], rotations.next().unwrap().into_rows());
+    /// assert_eq!(vec![vec![3, 1], vec![4, 2]], rotations.next().unwrap().into_rows());
     /// assert!(rotations.next().is_none());
     /// ```
     pub fn into_rotations(self) -> impl Iterator<Item = Self> {
@@ -382,6 +910,37 @@ where
     }
 }
 
+impl<T> PartialOrd for Canvas<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.rows().partial_cmp(other.rows())
+    }
+}
+
+impl<T> Ord for Canvas<T>
+where
+    T: Ord,
+{
+    /// Compares canvases row by row, lexicographically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let wide = Canvas::from_matrix([[1, 2, 3]]);
+    /// let tall = Canvas::from_matrix([[2], [1]]);
+    /// assert!(wide < tall);
+    /// assert!(Canvas::from_matrix([[1, 2]]) < wide);
+    /// assert!(wide < Canvas::from_matrix([[1, 2, 3], [0, 0, 0]]));
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rows().cmp(other.rows())
+    }
+}
+
 // noinspection DuplicatedCode
 impl<T, PT> Index<PT> for Canvas<T>
 where
@@ -407,7 +966,8 @@ where
     /// ```
     fn index(&self, index: PT) -> &Self::Output {
         let index = index.into();
-        &self.0[index.y.to_usize().unwrap()][index.x.to_usize().unwrap()]
+        self.get(index)
+            .unwrap_or_else(|| panic!("position {index} is outside the canvas"))
     }
 }
 
@@ -431,11 +991,12 @@ where
     /// let mut canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
     /// canvas[Pt::new(1, 1)] = 9;
     /// canvas[(2, 0)] = 7;
-    /// assert_eq!(vec![vec![1, 2, 7], vec![4, 9, 6]], canvas.0);
+    /// assert_eq!(vec![vec![1, 2, 7], vec![4, 9, 6]], canvas.into_rows());
     /// ```
     fn index_mut(&mut self, index: PT) -> &mut Self::Output {
         let index = index.into();
-        &mut self.0[index.y.to_usize().unwrap()][index.x.to_usize().unwrap()]
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("position {index} is outside the canvas"))
     }
 }
 
@@ -458,10 +1019,29 @@ impl<T> IntoIterator for Canvas<T> {
     /// assert_eq!(vec![1, 2, 3, 4, 5, 6], canvas.into_iter().flat_map(identity).collect::<Vec<_>>());
     /// ```
     fn into_iter(self) -> Self::IntoIter {
-        self.0
+        self.into_rows()
             .into_iter()
             .map(<_>::into_iter)
             .collect_vec()
             .into_iter()
     }
 }
+
+impl<T> Display for Canvas<T>
+where
+    T: Glyph,
+{
+    /// Renders the canvas as lines of text, using the pieces' [`Glyph`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[true, false], [false, true]]);
+    /// assert_eq!("#.\n.#", canvas.to_string());
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.render().fmt(f)
+    }
+}
@@ -1,6 +1,6 @@
 use std::array;
 use std::collections::HashMap;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
 use std::iter::{repeat_n, successors};
 use std::ops::{Index, IndexMut};
@@ -8,6 +8,9 @@ use std::ops::{Index, IndexMut};
 use itertools::Itertools;
 use num::ToPrimitive;
 
+use crate::anyhow::anyhow;
+use crate::mapping::canvas::TryFromLines;
+use crate::mapping::canvas::render::{Glyph, Render};
 use crate::positioning::Point;
 use crate::positioning::pt::{Pt, matrix_to_map};
 
@@ -192,6 +195,84 @@ impl<T, const W: usize, const H: usize> Canvas<T, W, H> {
         self.0.iter().map(|row| row.iter())
     }
 
+    /// Returns a reference to the canvas piece at the given [point](Pt), [wrapping](Pt::wrap)
+    /// the point around the canvas edges, as if the canvas was repeated infinitely in all
+    /// directions. Also works with coordinate tuples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::fixed::Canvas;
+    ///
+    /// let canvas = Canvas::from_array_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(&1, canvas.get_wrapping((3, 2)));
+    /// assert_eq!(&6, canvas.get_wrapping((-1, -1)));
+    /// ```
+    pub fn get_wrapping<PT>(&self, pt: PT) -> &T
+    where
+        PT: Into<Pt>,
+    {
+        let (x, y) = Self::wrapping_index(pt.into());
+        &self.0[y][x]
+    }
+
+    /// Returns a mutable reference to the canvas piece at the given [point](Pt),
+    /// [wrapping](Pt::wrap) the point around the canvas edges. Also works with coordinate tuples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::fixed::Canvas;
+    ///
+    /// let mut canvas = Canvas::from_array_matrix([[1, 2, 3], [4, 5, 6]]);
+    /// *canvas.get_wrapping_mut((-4, 5)) = 7;
+    /// assert_eq!([[1, 2, 3], [4, 5, 7]], canvas.0);
+    /// ```
+    pub fn get_wrapping_mut<PT>(&mut self, pt: PT) -> &mut T
+    where
+        PT: Into<Pt>,
+    {
+        let (x, y) = Self::wrapping_index(pt.into());
+        &mut self.0[y][x]
+    }
+
+    /// Returns a [`Render`] of this canvas, using the pieces' [`Glyph`] to render them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::fixed::Canvas;
+    /// # use aoclp::positioning::pt::Pt;
+    ///
+    /// let canvas = Canvas::from_array_matrix([[false; 3]; 2]);
+    /// let render = canvas
+    ///     .render()
+    ///     .path([Pt::new(2, 1), Pt::new(2, 0), Pt::new(1, 0)]);
+    /// assert_eq!(".←←\n..↑", render.to_string());
+    /// ```
+    pub fn render(&self) -> Render<'_, T>
+    where
+        T: Glyph,
+    {
+        self.render_with(T::glyph)
+    }
+
+    /// Returns a [`Render`] of this canvas, using a closure to convert every piece to a `char`.
+    pub fn render_with<'a, F>(&'a self, glyph: F) -> Render<'a, T>
+    where
+        F: Fn(&T) -> char + 'a,
+    {
+        Render::new(self.0.as_flattened(), W, H, glyph)
+    }
+
     /// Flips the canvas horizontally.
     ///
     /// # Examples
@@ -305,6 +386,12 @@ impl<T, const W: usize, const H: usize> Canvas<T, W, H> {
     {
         matrix_to_map(self.0)
     }
+
+    fn wrapping_index(pt: Pt) -> (usize, usize) {
+        assert!(W * H != 0, "cannot wrap around an empty canvas");
+        let pt = pt.wrap(W as i64, H as i64);
+        (pt.x as usize, pt.y as usize)
+    }
 }
 
 impl<T, const W: usize, const H: usize> Canvas<T, W, H>
@@ -400,6 +487,50 @@ where
     }
 }
 
+impl<T, const W: usize, const H: usize> TryFromLines<T> for Canvas<T, W, H> {
+    /// Tries to create a new [`Canvas`] from lines of text, using a closure to convert every
+    /// `char` into a piece of canvas.
+    ///
+    /// Returns an error if there are not exactly `H` lines of exactly `W` characters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::TryFromLines;
+    /// # use aoclp::mapping::canvas::fixed::Canvas;
+    ///
+    /// let canvas = Canvas::<_, 3, 2>::try_from_lines(["123", "456"], |c| c.to_digit(10).unwrap());
+    /// assert_eq!([[1, 2, 3], [4, 5, 6]], canvas.unwrap().0);
+    ///
+    /// assert!(Canvas::<_, 3, 2>::try_from_lines(["123", "45"], |c| c).is_err());
+    /// assert!(Canvas::<_, 3, 2>::try_from_lines(["123"], |c| c).is_err());
+    /// ```
+    fn try_from_lines<F, I, S>(lines: I, mut f: F) -> crate::Result<Self>
+    where
+        F: FnMut(char) -> T,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let rows: Vec<[T; W]> = lines
+            .into_iter()
+            .map(|s| {
+                let s = s.as_ref();
+                s.chars()
+                    .map(&mut f)
+                    .collect_array()
+                    .ok_or_else(|| anyhow!("expected a row of width {W}, found \"{s}\""))
+            })
+            .try_collect()?;
+
+        let height = rows.len();
+        Ok(Self(
+            rows.into_iter()
+                .collect_array()
+                .ok_or_else(|| anyhow!("expected {H} rows, found {height}"))?,
+        ))
+    }
+}
+
 impl<T, const W: usize, const H: usize> Default for Canvas<T, W, H>
 where
     T: Default + Clone,
@@ -503,3 +634,22 @@ impl<T, const W: usize, const H: usize> IntoIterator for Canvas<T, W, H> {
             .into_iter()
     }
 }
+
+impl<T, const W: usize, const H: usize> Display for Canvas<T, W, H>
+where
+    T: Glyph,
+{
+    /// Renders the canvas as lines of text, using the pieces' [`Glyph`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::fixed::Canvas;
+    ///
+    /// let canvas = Canvas::from_array_matrix([['a', 'b'], ['c', 'd']]);
+    /// assert_eq!("ab\ncd", canvas.to_string());
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.render().fmt(f)
+    }
+}
@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use gratte::IntoEnumIterator;
+use itertools::Itertools;
+
+use crate::anyhow::Context;
+use crate::positioning::direction::MovementDirection;
+use crate::positioning::direction::four_points::Direction4;
+use crate::positioning::pt::Pt;
+
+/// Trait implemented by canvas pieces that have a default `char` representation
+/// when [rendered](Render).
+pub trait Glyph {
+    /// Returns the `char` used to render this piece.
+    fn glyph(&self) -> char;
+}
+
+impl Glyph for char {
+    fn glyph(&self) -> char {
+        *self
+    }
+}
+
+impl Glyph for bool {
+    /// Renders `true` as `#` and `false` as `.`.
+    fn glyph(&self) -> char {
+        if *self { '#' } else { '.' }
+    }
+}
+
+/// A 24-bit RGB colour, used for ANSI output and image export of a [`Render`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+    pub const RED: Self = Self(255, 0, 0);
+    pub const GREEN: Self = Self(0, 255, 0);
+    pub const BLUE: Self = Self(0, 0, 255);
+    pub const YELLOW: Self = Self(255, 255, 0);
+}
+
+/// Builder used to render a canvas as text or as an image.
+///
+/// The text output is generated by the [`Display`] implementation, one line per canvas row.
+/// Pieces are rendered using a closure mapping them to `char`s (see [`Glyph`] for default
+/// mappings), over which [paths](Self::path) and [marks](Self::mark) can be overlaid.
+/// If a [colour](Self::colour) closure is provided, the text output includes ANSI colour codes
+/// (overlays then use the [overlay colour](Self::overlay_colour)).
+///
+/// Renders can also be exported as [PPM](Self::to_ppm) or [PNG](Self::to_png) images, where
+/// each canvas piece is drawn as a square of pixels.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::canvas::dynamic::Canvas;
+/// # use aoclp::positioning::pt::Pt;
+///
+/// let canvas = Canvas::from_lines(["....", ".#..", "...."], |c| c == '#');
+/// let render = canvas
+///     .render()
+///     .path([Pt::new(0, 0), Pt::new(0, 1), Pt::new(0, 2), Pt::new(1, 2)])
+///     .mark(Pt::new(3, 0), 'E');
+/// assert_eq!("↓..E\n↓#..\n→→..", render.to_string());
+/// ```
+pub struct Render<'a, T> {
+    piece: PieceFn<'a, T>,
+    width: usize,
+    height: usize,
+    origin: Pt,
+    glyph: GlyphFn<'a, T>,
+    colour: Option<ColourFn<'a, T>>,
+    overlay: HashMap<Pt<usize>, char>,
+    overlay_colour: Rgb,
+    scale: usize,
+}
+
+type PieceFn<'a, T> = Box<dyn Fn(usize, usize) -> &'a T + 'a>;
+type GlyphFn<'a, T> = Box<dyn Fn(&T) -> char + 'a>;
+type ColourFn<'a, T> = Box<dyn Fn(&T) -> Option<Rgb> + 'a>;
+
+impl<'a, T> Render<'a, T> {
+    /// Creates a render of a canvas of size `width` by `height` whose `pieces` are stored
+    /// in row-major order, using a closure to convert every piece to a `char`.
+    ///
+    /// Canvases usually provide a `render` or `render_with` method that calls this.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of pieces is not `width * height`.
+    pub fn new<F>(pieces: &'a [T], width: usize, height: usize, glyph: F) -> Self
+    where
+        F: Fn(&T) -> char + 'a,
+    {
+        assert_eq!(width * height, pieces.len(), "pieces do not fit a {width}x{height} canvas");
+
+        Self::from_fn(width, height, move |x, y| &pieces[y * width + x], glyph)
+    }
+
+    /// Creates a render of a canvas of size `width` by `height`, using a closure to fetch
+    /// the piece at position `(x, y)` and another to convert every piece to a `char`.
+    ///
+    /// Useful for canvases that do not store their pieces in a contiguous slice.
+    pub fn from_fn<P, F>(width: usize, height: usize, piece: P, glyph: F) -> Self
+    where
+        P: Fn(usize, usize) -> &'a T + 'a,
+        F: Fn(&T) -> char + 'a,
+    {
+        Self {
+            piece: Box::new(piece),
+            width,
+            height,
+            origin: Pt::new(0, 0),
+            glyph: Box::new(glyph),
+            colour: None,
+            overlay: HashMap::new(),
+            overlay_colour: Rgb::RED,
+            scale: 1,
+        }
+    }
+
+    /// Sets the closure used to convert canvas pieces to `char`s.
+    pub fn glyph<F>(mut self, glyph: F) -> Self
+    where
+        F: Fn(&T) -> char + 'a,
+    {
+        self.glyph = Box::new(glyph);
+        self
+    }
+
+    /// Sets a closure returning the colour of canvas pieces.
+    ///
+    /// When set, the text output colours pieces using ANSI escape codes; pieces for which
+    /// the closure returns `None` are left uncoloured. This closure is also used for
+    /// image export.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    /// # use aoclp::mapping::canvas::render::Rgb;
+    ///
+    /// let canvas = Canvas::from_matrix([[true, false]]);
+    /// let render = canvas.render().colour(|&b| b.then_some(Rgb::GREEN));
+    /// assert_eq!("\x1b[38;2;0;255;0m#\x1b[0m.", render.to_string());
+    /// ```
+    pub fn colour<F>(mut self, colour: F) -> Self
+    where
+        F: Fn(&T) -> Option<Rgb> + 'a,
+    {
+        self.colour = Some(Box::new(colour));
+        self
+    }
+
+    /// Overlays a path over the canvas.
+    ///
+    /// Each point of the path is drawn using the [emoji](Direction4::emoji) of the direction
+    /// taken to reach the next point; the last point uses the direction taken to reach it.
+    /// Consecutive points that are not orthogonally adjacent are not drawn, as are points
+    /// outside the canvas.
+    ///
+    /// Can be used with the output of [`assemble_path`](crate::mapping::dij::assemble_path).
+    pub fn path<I>(mut self, path: I) -> Self
+    where
+        I: IntoIterator<Item = Pt>,
+    {
+        let mut last = None;
+        for (a, b) in path.into_iter().tuple_windows() {
+            if let Some(direction) = Direction4::iter().find(|d| a + d.displacement() == b) {
+                self = self.mark(a, direction.emoji());
+                last = Some((b, direction));
+            }
+        }
+        match last {
+            Some((pt, direction)) => self.mark(pt, direction.emoji()),
+            None => self,
+        }
+    }
+
+    /// Overlays a single `char` over the canvas at position `pt`.
+    /// Points outside the canvas are ignored.
+    pub fn mark(mut self, pt: Pt, c: char) -> Self {
+        let pt = pt - self.origin;
+        if let (Ok(x), Ok(y)) = (usize::try_from(pt.x), usize::try_from(pt.y))
+            && x < self.width
+            && y < self.height
+        {
+            self.overlay.insert(Pt::new(x, y), c);
+        }
+        self
+    }
+
+    /// Sets the position of the canvas' top-left piece, relative to which [paths](Self::path)
+    /// and [marks](Self::mark) are placed. Defaults to `(0, 0)`.
+    pub fn origin(mut self, origin: Pt) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Sets the colour of [paths](Self::path) and [marks](Self::mark) in ANSI and image output.
+    /// Defaults to [red](Rgb::RED).
+    pub fn overlay_colour(mut self, colour: Rgb) -> Self {
+        self.overlay_colour = colour;
+        self
+    }
+
+    /// Sets the size, in pixels, of the square used to draw each canvas piece in image
+    /// output. Defaults to `1`.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Exports the render as a binary PPM (`P6`) image.
+    ///
+    /// Pieces are drawn using the [colour](Self::colour) closure if set and if it returns a
+    /// colour; otherwise, pieces rendered as `.` or ` ` are drawn in black, others in white.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::from_matrix([[true, false]]);
+    /// let ppm = canvas.render().to_ppm();
+    /// assert_eq!(b"P6\n2 1\n255\n\xff\xff\xff\x00\x00\x00", ppm.as_slice());
+    /// ```
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let (width, height) = self.image_size();
+        let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+        for y in 0..height {
+            ppm.extend(self.scanline(y));
+        }
+        ppm
+    }
+
+    /// Exports the render as a PNG image, using the same colours as [`to_ppm`](Self::to_ppm).
+    ///
+    /// Image data is stored without compression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    ///
+    /// let canvas = Canvas::of(100, 50, false);
+    /// let png = canvas.render().scale(2).to_png();
+    /// assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+    /// assert_eq!(b"IHDR\0\0\0\xc8\0\0\0\x64", &png[12..24]);
+    /// assert_eq!(b"IEND\xae\x42\x60\x82", &png[png.len() - 8..]);
+    /// ```
+    pub fn to_png(&self) -> Vec<u8> {
+        let (width, height) = self.image_size();
+        let (width, height) = (
+            u32::try_from(width).expect("image too wide"),
+            u32::try_from(height).expect("image too tall"),
+        );
+
+        let mut header = Vec::with_capacity(13);
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        header.extend([8, 2, 0, 0, 0]); // 8-bit RGB, no interlacing
+
+        let mut data = Vec::new();
+        for y in 0..height as usize {
+            data.push(0); // no filter
+            data.extend(self.scanline(y));
+        }
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_png_chunk(&mut png, b"IHDR", &header);
+        write_png_chunk(&mut png, b"IDAT", &zlib_stored(&data));
+        write_png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Saves the render to a file as a [PPM image](Self::to_ppm).
+    pub fn save_ppm<P>(&self, path: P) -> crate::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_ppm())
+            .with_context(|| format!("failed to write PPM image to {}", path.display()))
+    }
+
+    /// Saves the render to a file as a [PNG image](Self::to_png).
+    pub fn save_png<P>(&self, path: P) -> crate::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_png())
+            .with_context(|| format!("failed to write PNG image to {}", path.display()))
+    }
+
+    fn cell(&self, x: usize, y: usize) -> (char, Option<Rgb>) {
+        match self.overlay.get(&Pt::new(x, y)) {
+            Some(&c) => (c, Some(self.overlay_colour)),
+            None => {
+                let piece = (self.piece)(x, y);
+                ((self.glyph)(piece), self.colour.as_ref().and_then(|colour| colour(piece)))
+            },
+        }
+    }
+
+    fn image_size(&self) -> (usize, usize) {
+        (self.width * self.scale, self.height * self.scale)
+    }
+
+    fn scanline(&self, y: usize) -> impl Iterator<Item = u8> + '_ {
+        (0..self.width * self.scale).flat_map(move |x| {
+            let (c, colour) = self.cell(x / self.scale, y / self.scale);
+            let Rgb(r, g, b) = colour.unwrap_or(match c {
+                '.' | ' ' => Rgb::BLACK,
+                _ => Rgb::WHITE,
+            });
+            [r, g, b]
+        })
+    }
+}
+
+impl<T> Display for Render<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y != 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                match self.cell(x, y) {
+                    (c, Some(Rgb(r, g, b))) if self.colour.is_some() => {
+                        write!(f, "\x1b[38;2;{r};{g};{b}m{c}\x1b[0m")?
+                    },
+                    (c, _) => write!(f, "{c}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let len = u32::try_from(data.len()).expect("PNG chunk too large");
+    png.extend(len.to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(u8::from(blocks.peek().is_none()));
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(block);
+    }
+    zlib.extend(adler32(data).to_be_bytes());
+    zlib
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (a, b) = data.chunks(5552).fold((1u32, 0u32), |(a, b), chunk| {
+        let (a, b) = chunk.iter().fold((a, b), |(a, b), &byte| {
+            let a = a + u32::from(byte);
+            (a, b + a)
+        });
+        (a % MOD, b % MOD)
+    });
+    (b << 16) | a
+}
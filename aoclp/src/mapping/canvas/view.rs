@@ -0,0 +1,255 @@
+use std::ops::Index;
+
+use num::ToPrimitive;
+
+use crate::mapping::canvas::dynamic::Canvas;
+use crate::positioning::Point;
+use crate::positioning::pt::Pt;
+
+/// One of the eight ways a rectangular [`Canvas`] can be rotated and/or flipped.
+///
+/// Flips use the same terminology as [`Canvas::flip_horizontally`] and [`Canvas::flip_vertically`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Transform {
+    /// Canvas is left as-is.
+    Identity,
+    /// Canvas is [rotated 90 degrees to the left](Canvas::rotate_left).
+    RotateLeft,
+    /// Canvas is rotated 180 degrees.
+    Rotate180,
+    /// Canvas is rotated 90 degrees to the right.
+    RotateRight,
+    /// Canvas is [flipped horizontally](Canvas::flip_horizontally) (i.e. upside-down).
+    FlipHorizontally,
+    /// Canvas is [flipped vertically](Canvas::flip_vertically) (i.e. mirrored).
+    FlipVertically,
+    /// Canvas is flipped along its main diagonal, so that rows become columns.
+    Transpose,
+    /// Canvas is flipped along its anti-diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    /// All possible transforms.
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::RotateLeft,
+        Self::Rotate180,
+        Self::RotateRight,
+        Self::FlipHorizontally,
+        Self::FlipVertically,
+        Self::Transpose,
+        Self::AntiTranspose,
+    ];
+
+    /// Returns `true` if this transform swaps the width and height of a canvas.
+    pub fn swaps_axes(&self) -> bool {
+        matches!(self, Self::RotateLeft | Self::RotateRight | Self::Transpose | Self::AntiTranspose)
+    }
+
+    /// Given a position `(x, y)` in a transformed canvas, returns the corresponding
+    /// position in the original canvas of size `width` by `height`.
+    fn source(&self, (x, y): (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Self::Identity => (x, y),
+            Self::RotateLeft => (width - 1 - y, x),
+            Self::Rotate180 => (width - 1 - x, height - 1 - y),
+            Self::RotateRight => (y, height - 1 - x),
+            Self::FlipHorizontally => (x, height - 1 - y),
+            Self::FlipVertically => (width - 1 - x, y),
+            Self::Transpose => (y, x),
+            Self::AntiTranspose => (width - 1 - y, height - 1 - x),
+        }
+    }
+}
+
+/// A borrowed view of a [`Canvas`] with a [`Transform`] applied.
+///
+/// Views can be indexed, iterated and compared in transformed coordinates without
+/// copying any canvas pieces. To get an owned canvas, use [`to_canvas`](Self::to_canvas).
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::canvas::dynamic::Canvas;
+/// # use aoclp::mapping::canvas::view::Transform;
+///
+/// let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+///
+/// let view = canvas.view(Transform::RotateLeft);
+/// assert_eq!(2, view.width());
+/// assert_eq!(3, view.height());
+/// assert_eq!(3, view[(0, 0)]);
+/// assert_eq!(view, canvas.clone().rotate_left());
+///
+/// let pattern = Canvas::from_matrix([[4, 1], [5, 2], [6, 3]]);
+/// let matching = canvas.views().find(|view| *view == pattern).unwrap();
+/// assert_eq!(Transform::RotateRight, matching.transform());
+/// ```
+#[derive(Debug)]
+pub struct CanvasView<'a, T> {
+    canvas: &'a Canvas<T>,
+    transform: Transform,
+}
+
+impl<'a, T> CanvasView<'a, T> {
+    /// Creates a view of `canvas` with the given `transform` applied.
+    pub fn new(canvas: &'a Canvas<T>, transform: Transform) -> Self {
+        Self { canvas, transform }
+    }
+
+    /// Returns the transform applied by this view.
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Returns the width of the transformed canvas.
+    pub fn width(&self) -> usize {
+        match self.transform.swaps_axes() {
+            true => self.canvas.height(),
+            false => self.canvas.width(),
+        }
+    }
+
+    /// Returns the height of the transformed canvas.
+    pub fn height(&self) -> usize {
+        match self.transform.swaps_axes() {
+            true => self.canvas.width(),
+            false => self.canvas.height(),
+        }
+    }
+
+    /// Returns a reference to the piece at the given [point](Pt) in the transformed canvas,
+    /// or `None` if the point is out of bounds. Also works with coordinate tuples.
+    ///
+    /// The top-left canvas piece is at position `(0, 0)`.
+    pub fn get<PT>(&self, pt: PT) -> Option<&'a T>
+    where
+        PT: Into<Pt>,
+    {
+        let pt = pt.into();
+        let (x, y) = (pt.x.to_usize()?, pt.y.to_usize()?);
+        (x < self.width() && y < self.height()).then(|| self.piece(x, y))
+    }
+
+    /// Returns an [iterator] that iterates the transformed canvas' rows. Each row is itself
+    /// an [iterator] that returns references to the canvas pieces.
+    ///
+    /// [iterator]: DoubleEndedIterator
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &'a T>> + use<'a, '_, T>
+    {
+        (0..self.height()).map(move |y| (0..self.width()).map(move |x| self.piece(x, y)))
+    }
+
+    /// Returns an owned [`Canvas`] containing the transformed pieces.
+    pub fn to_canvas(&self) -> Canvas<T>
+    where
+        T: Clone,
+    {
+        Canvas::from_matrix(self.iter().map(|row| row.cloned()))
+    }
+
+    fn piece(&self, x: usize, y: usize) -> &'a T {
+        let (x, y) = self
+            .transform
+            .source((x, y), self.canvas.width(), self.canvas.height());
+        &self.canvas.as_slice()[y * self.canvas.width() + x]
+    }
+}
+
+impl<T> Clone for CanvasView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CanvasView<'_, T> {}
+
+impl<T, U> PartialEq<CanvasView<'_, U>> for CanvasView<'_, T>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &CanvasView<'_, U>) -> bool {
+        self.width() == other.width()
+            && self.height() == other.height()
+            && self
+                .iter()
+                .flatten()
+                .zip(other.iter().flatten())
+                .all(|(a, b)| a == b)
+    }
+}
+
+impl<T> Eq for CanvasView<'_, T> where T: Eq {}
+
+impl<T, U> PartialEq<Canvas<U>> for CanvasView<'_, T>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &Canvas<U>) -> bool {
+        *self == other.view(Transform::Identity)
+    }
+}
+
+// noinspection DuplicatedCode
+impl<T, PT> Index<PT> for CanvasView<'_, T>
+where
+    PT: Into<Pt>,
+    <Pt as Point>::Coord: ToPrimitive,
+{
+    type Output = T;
+
+    /// Returns a reference to the piece at the given [point](Pt) in the transformed canvas.
+    /// Also works with coordinate tuples.
+    ///
+    /// The top-left canvas piece is at position `(0, 0)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point is out of bounds.
+    fn index(&self, index: PT) -> &Self::Output {
+        let index = index.into();
+        self.get(index)
+            .unwrap_or_else(|| panic!("position {index} out of bounds"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms() {
+        let canvas = Canvas::from_matrix([[1, 2, 3], [4, 5, 6]]);
+        let left = canvas.clone().rotate_left();
+
+        for transform in Transform::ALL {
+            let expected = match transform {
+                Transform::Identity => canvas.clone(),
+                Transform::RotateLeft => left.clone(),
+                Transform::Rotate180 => left.clone().rotate_left(),
+                Transform::RotateRight => left.clone().rotate_left().rotate_left(),
+                Transform::FlipHorizontally => canvas.clone().flip_horizontally(),
+                Transform::FlipVertically => canvas.clone().flip_vertically(),
+                Transform::Transpose => left.clone().flip_horizontally(),
+                Transform::AntiTranspose => left.clone().flip_vertically(),
+            };
+
+            let view = canvas.view(transform);
+            assert_eq!(expected.width(), view.width(), "{transform:?}");
+            assert_eq!(expected.height(), view.height(), "{transform:?}");
+            assert_eq!(expected, view.to_canvas(), "{transform:?}");
+            for y in -1..=3 {
+                for x in -1..=3 {
+                    assert_eq!(
+                        expected.get((x, y)),
+                        view.get((x, y)),
+                        "{transform:?} at ({x}, {y})"
+                    );
+                }
+            }
+        }
+    }
+}
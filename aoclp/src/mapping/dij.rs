@@ -1,64 +1,272 @@
+use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::hash::Hash;
+use std::iter::from_fn;
+use std::ops::Add;
 
-pub trait Graph<T> {
+use itertools::Itertools;
+
+use crate::num::{BigUint, One, Zero};
+
+/// Trait implemented by types that can be used as the cost of edges in a [`Graph`].
+///
+/// Implemented automatically for all types that implement [`One`]; other types can
+/// implement it manually.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Zero {
+    /// Returns the cost of a single edge in an unweighted graph.
+    fn unit() -> Self;
+}
+
+impl<C> Cost for C
+where
+    C: Copy + Ord + Add<Output = C> + Zero + One,
+{
+    fn unit() -> Self {
+        C::one()
+    }
+}
+
+pub trait Graph<T, C: Cost = usize> {
     fn neighbours(&self, node: &T) -> impl Iterator<Item = T>;
 
-    fn dist(&self, a: &T, b: &T) -> usize {
+    fn dist(&self, a: &T, b: &T) -> C {
         let (_, _) = (a, b);
-        1
+        C::unit()
     }
 }
 
-pub struct Output<T> {
-    pub dist: HashMap<T, usize>,
+pub struct Output<T, C = usize> {
+    pub dist: HashMap<T, C>,
     pub prev: HashMap<T, T>,
 }
 
-pub fn build<T, G>(graph: &G, start: T) -> Output<T>
+/// Output of [`build_all`]: like [`Output`], but keeps every predecessor of each node
+/// through which a shortest path passes.
+pub struct AllOutput<T, C = usize> {
+    pub dist: HashMap<T, C>,
+    pub prev: HashMap<T, Vec<T>>,
+}
+
+impl<T, C> AllOutput<T, C>
+where
+    T: Clone + Eq + Hash,
+    C: Cost,
+{
+    /// Returns the number of distinct shortest paths from `start` to `end`.
+    ///
+    /// Returns zero if `end` was not reached by the search.
+    pub fn path_count(&self, start: &T, end: &T) -> BigUint {
+        if !self.dist.contains_key(end) {
+            return BigUint::zero();
+        }
+
+        // Memoised DFS over the predecessor graph: a node's count is only computed once
+        // the counts of all its predecessors are known. Sorting by distance would not work,
+        // because zero-cost edges link nodes that are at the same distance from `start`.
+        let mut counts: HashMap<T, BigUint> = HashMap::new();
+        counts.insert(start.clone(), BigUint::one());
+
+        let mut stack = vec![(end.clone(), false)];
+        while let Some((n, expanded)) = stack.pop() {
+            if counts.contains_key(&n) {
+                continue;
+            }
+
+            let prev = self.prev.get(&n).into_iter().flatten();
+            if expanded {
+                let count = prev.filter_map(|p| counts.get(p)).sum();
+                counts.insert(n, count);
+            } else {
+                let pending = prev
+                    .filter(|p| !counts.contains_key(*p))
+                    .cloned()
+                    .collect_vec();
+                stack.push((n, true));
+                stack.extend(pending.into_iter().map(|p| (p, false)));
+            }
+        }
+
+        counts.remove(end).unwrap_or_default()
+    }
+
+    /// Returns an [iterator](Iterator) of all distinct shortest paths from `start` to `end`.
+    ///
+    /// Paths are generated lazily, but there can be exponentially many of them.
+    pub fn paths<'a>(&'a self, start: &'a T, end: &T) -> impl Iterator<Item = Vec<T>> + 'a {
+        let mut stack = match self.dist.contains_key(end) {
+            true => vec![vec![end.clone()]],
+            false => vec![],
+        };
+
+        from_fn(move || {
+            while let Some(path) = stack.pop() {
+                let n = path.last().unwrap();
+                if n == start {
+                    return Some(path.into_iter().rev().collect());
+                }
+                for p in self.prev.get(n).into_iter().flatten() {
+                    let mut next = path.clone();
+                    next.push(p.clone());
+                    stack.push(next);
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns the set of all nodes that are on at least one shortest path
+    /// from `start` to `end`, including `start` and `end` themselves.
+    ///
+    /// Returns an empty set if `end` was not reached by the search.
+    pub fn path_nodes(&self, start: &T, end: &T) -> HashSet<T> {
+        let mut nodes = HashSet::new();
+        if !self.dist.contains_key(end) {
+            return nodes;
+        }
+
+        let mut stack = vec![end.clone()];
+        while let Some(n) = stack.pop() {
+            if n != *start {
+                stack.extend(
+                    self.prev
+                        .get(&n)
+                        .into_iter()
+                        .flatten()
+                        .filter(|p| !nodes.contains(*p))
+                        .cloned(),
+                );
+            }
+            nodes.insert(n);
+        }
+        nodes
+    }
+}
+
+pub fn build<T, G, C>(graph: &G, start: T) -> Output<T, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    build_until(graph, start, |_| false)
+}
+
+/// Runs Dijkstra's algorithm like [`build`], but stops as soon as the closest node
+/// for which `is_target` returns `true` is reached.
+///
+/// When stopping early, only the distances of the target and of the nodes closer to
+/// `start` than the target are guaranteed to be final.
+pub fn build_until<T, G, C, F>(graph: &G, start: T, is_target: F) -> Output<T, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+    F: FnMut(&T) -> bool,
+{
+    let mut prev = HashMap::new();
+    let dist = search(graph, start, is_target, |u, v, ordering| {
+        if ordering == Ordering::Less {
+            prev.insert(v.clone(), u.clone());
+        }
+    });
+
+    Output { dist, prev }
+}
+
+/// Runs Dijkstra's algorithm like [`build`], but keeps track of all predecessors
+/// through which a shortest path passes, allowing enumeration of all shortest paths.
+///
+/// Edges with a cost of zero are followed, but they are only recorded as predecessors
+/// when they lead to a strictly shorter path; otherwise, two nodes linked by zero-cost
+/// edges would each become the other's predecessor. Shortest paths are therefore only
+/// all enumerated if every edge has a non-zero cost.
+pub fn build_all<T, G, C>(graph: &G, start: T) -> AllOutput<T, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    build_all_until(graph, start, |_| false)
+}
+
+/// Combination of [`build_all`] and [`build_until`].
+///
+/// The same restriction on zero-cost edges as [`build_all`] applies.
+pub fn build_all_until<T, G, C, F>(graph: &G, start: T, is_target: F) -> AllOutput<T, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+    F: FnMut(&T) -> bool,
+{
+    let mut prev: HashMap<T, Vec<T>> = HashMap::new();
+    let dist = search(graph, start, is_target, |u, v, ordering| {
+        if ordering == Ordering::Equal && graph.dist(u, v).is_zero() {
+            return;
+        }
+
+        let preds = prev.entry(v.clone()).or_default();
+        if ordering == Ordering::Less {
+            preds.clear();
+        }
+        if !preds.contains(u) {
+            preds.push(u.clone());
+        }
+    });
+
+    AllOutput { dist, prev }
+}
+
+/// Core of Dijkstra's algorithm. Calls `relax` with `(u, v, ordering)` every time
+/// a path to `v` going through `u` is found that is shorter ([`Ordering::Less`]) or
+/// as short as ([`Ordering::Equal`]) the best path known so far.
+fn search<T, G, C, F, R>(graph: &G, start: T, mut is_target: F, mut relax: R) -> HashMap<T, C>
 where
     T: Clone + Eq + Hash,
-    G: Graph<T>,
+    G: Graph<T, C>,
+    C: Cost,
+    F: FnMut(&T) -> bool,
+    R: FnMut(&T, &T, Ordering),
 {
     // https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm
 
     let mut dist = HashMap::new();
-    let mut prev = HashMap::new();
-    dist.insert(start.clone(), 0);
+    dist.insert(start.clone(), C::zero());
 
-    let mut q = HashSet::new();
-    q.insert(start.clone());
+    let mut q = BinaryHeap::new();
+    q.push(Visit { cost: C::zero(), node: start });
 
-    while !q.is_empty() {
-        let u = q
-            .iter()
-            .map(|n| (n.clone(), dist.get(n).copied().unwrap_or(usize::MAX)))
-            .min_by_key(|(_, u_dist)| *u_dist);
-        match u {
-            None => break,
-            Some((u, u_dist)) => {
-                q.remove(&u);
-                graph.neighbours(&u).for_each(|v| {
-                    let alt = u_dist + graph.dist(&u, &v);
-                    match dist.entry(v.clone()) {
-                        Entry::Vacant(e) => {
-                            q.insert(v.clone());
-                            e.insert(alt);
-                            prev.insert(v, u.clone());
-                        },
-                        Entry::Occupied(mut e) if alt < *e.get() => {
-                            e.insert(alt);
-                            prev.insert(v, u.clone());
-                        },
-                        _ => (),
-                    }
-                });
-            },
+    while let Some(Visit { cost: u_dist, node: u }) = q.pop() {
+        if dist.get(&u).is_some_and(|d| u_dist > *d) {
+            continue;
         }
+        if is_target(&u) {
+            break;
+        }
+
+        graph.neighbours(&u).for_each(|v| {
+            let alt = u_dist + graph.dist(&u, &v);
+            match dist.entry(v.clone()) {
+                Entry::Vacant(e) => {
+                    e.insert(alt);
+                    relax(&u, &v, Ordering::Less);
+                    q.push(Visit { cost: alt, node: v });
+                },
+                Entry::Occupied(mut e) => match alt.cmp(e.get()) {
+                    Ordering::Less => {
+                        e.insert(alt);
+                        relax(&u, &v, Ordering::Less);
+                        q.push(Visit { cost: alt, node: v });
+                    },
+                    Ordering::Equal => relax(&u, &v, Ordering::Equal),
+                    Ordering::Greater => (),
+                },
+            }
+        });
     }
 
-    Output { dist, prev }
+    dist
 }
 
 pub fn assemble_path<T>(prev: &HashMap<T, T>, start: &T, end: &T) -> impl Iterator<Item = T>
@@ -77,3 +285,208 @@ where
     path.push(start.clone());
     path.into_iter().rev()
 }
+
+/// Entry in a priority queue, ordered so that the lowest cost is popped first.
+pub(crate) struct Visit<T, C> {
+    pub cost: C,
+    pub node: T,
+}
+
+impl<T, C> PartialEq for Visit<T, C>
+where
+    C: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<T, C> Eq for Visit<T, C> where C: Eq {}
+
+impl<T, C> PartialOrd for Visit<T, C>
+where
+    C: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, C> Ord for Visit<T, C>
+where
+    C: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    /// Small weighted graph: `a -1-> b -1-> c -1-> d` plus a costly shortcut `a -5-> d`.
+    struct Weighted;
+
+    impl Graph<char, i64> for Weighted {
+        fn neighbours(&self, node: &char) -> impl Iterator<Item = char> {
+            match node {
+                'a' => vec!['b', 'd'],
+                'b' => vec!['c'],
+                'c' => vec!['d'],
+                _ => vec![],
+            }
+            .into_iter()
+        }
+
+        fn dist(&self, a: &char, b: &char) -> i64 {
+            match (a, b) {
+                ('a', 'd') => 5,
+                _ => 1,
+            }
+        }
+    }
+
+    struct Line(usize);
+
+    impl Graph<usize> for Line {
+        fn neighbours(&self, node: &usize) -> impl Iterator<Item = usize> {
+            [node.checked_sub(1), Some(node + 1).filter(|n| *n < self.0)]
+                .into_iter()
+                .flatten()
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let output = build(&Weighted, 'a');
+        assert_eq!(HashMap::from([('a', 0), ('b', 1), ('c', 2), ('d', 3)]), output.dist);
+        assert_eq!("abcd", assemble_path(&output.prev, &'a', &'d').collect::<String>());
+
+        let output = build(&Line(10), 3);
+        assert_eq!(10, output.dist.len());
+        assert_eq!(Some(&6), output.dist.get(&9));
+    }
+
+    #[test]
+    fn test_build_until() {
+        let output = build_until(&Line(1_000), 0, |n| *n == 5);
+        assert_eq!(Some(&5), output.dist.get(&5));
+        assert!(output.dist.len() < 10);
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], assemble_path(&output.prev, &0, &5).collect_vec());
+    }
+
+    /// Grid of `w` by `h` nodes where one can only move right or down.
+    struct Lattice(usize, usize);
+
+    impl Graph<(usize, usize)> for Lattice {
+        fn neighbours(&self, node: &(usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+            let (x, y) = *node;
+            [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|(x, y)| *x < self.0 && *y < self.1)
+        }
+    }
+
+    #[test]
+    fn test_build_all() {
+        let output = build_all(&Weighted, 'a');
+        assert_eq!(vec!['c'], output.prev[&'d']);
+        assert_eq!(BigUint::one(), output.path_count(&'a', &'d'));
+
+        let output = build_all(&Lattice(3, 3), (0, 0));
+        assert_eq!(BigUint::from(6u32), output.path_count(&(0, 0), &(2, 2)));
+        assert_eq!(BigUint::from(3u32), output.path_count(&(0, 0), &(1, 2)));
+        assert_eq!(6, output.paths(&(0, 0), &(2, 2)).unique().count());
+        assert!(output.paths(&(0, 0), &(2, 2)).all(|p| p.len() == 5));
+        assert_eq!(4, output.path_nodes(&(0, 0), &(1, 1)).len());
+        assert_eq!(BigUint::zero(), output.path_count(&(0, 0), &(3, 3)));
+        assert_eq!(0, output.paths(&(0, 0), &(3, 3)).count());
+
+        let output = build_all(&Lattice(40, 40), (0, 0));
+        let expected: BigUint = "27217014869199032015600".parse().unwrap();
+        assert_eq!(expected, output.path_count(&(0, 0), &(39, 39)));
+    }
+
+    /// Graph where `a` and `b` are linked both ways by a free edge, and both lead to `c`.
+    struct Free;
+
+    impl Graph<char, u32> for Free {
+        fn neighbours(&self, node: &char) -> impl Iterator<Item = char> {
+            match node {
+                'a' => vec!['b', 'c'],
+                'b' => vec!['a', 'c'],
+                _ => vec![],
+            }
+            .into_iter()
+        }
+
+        fn dist(&self, a: &char, b: &char) -> u32 {
+            match (a, b) {
+                ('a', 'b') | ('b', 'a') => 0,
+                _ => 1,
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_all_zero_cost() {
+        let output = build_all(&Free, 'a');
+        assert_eq!(HashMap::from([('a', 0), ('b', 0), ('c', 1)]), output.dist);
+        assert_eq!(BigUint::from(2u32), output.path_count(&'a', &'c'));
+        assert_eq!(
+            vec!["ac".to_string(), "abc".to_string()],
+            output
+                .paths(&'a', &'c')
+                .map(String::from_iter)
+                .sorted_by_key(String::len)
+                .collect_vec()
+        );
+        assert_eq!(3, output.path_nodes(&'a', &'c').len());
+    }
+
+    /// Graph where the only shortest path to `t` goes through a free edge: `s -1-> x -0-> y -1-> t`,
+    /// with a costly shortcut `s -5-> y`.
+    struct FreeDetour;
+
+    impl Graph<char, u32> for FreeDetour {
+        fn neighbours(&self, node: &char) -> impl Iterator<Item = char> {
+            match node {
+                's' => vec!['x', 'y'],
+                'x' => vec!['y'],
+                'y' => vec!['t'],
+                _ => vec![],
+            }
+            .into_iter()
+        }
+
+        fn dist(&self, a: &char, b: &char) -> u32 {
+            match (a, b) {
+                ('s', 'y') => 5,
+                ('x', 'y') => 0,
+                _ => 1,
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_count_zero_cost() {
+        // `x` and `y` are at the same distance, so repeat to exercise different hash orders.
+        for _ in 0..20 {
+            let output = build_all(&FreeDetour, 's');
+            assert_eq!(BigUint::one(), output.path_count(&'s', &'t'));
+        }
+
+        let output = build_all(&FreeDetour, 's');
+        assert_eq!(HashMap::from([('s', 0), ('x', 1), ('y', 1), ('t', 2)]), output.dist);
+        assert_eq!(
+            vec!["sxyt".to_string()],
+            output
+                .paths(&'s', &'t')
+                .map(String::from_iter)
+                .collect_vec()
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use std::path::Path;
+
+use itertools::Itertools;
+
+use crate::anyhow::Context;
+use crate::mapping::dij::{Cost, Graph};
+use crate::mapping::graph::DiGraph;
+
+/// Builder used to export a graph in [Graphviz](https://graphviz.org/) DOT format.
+///
+/// The DOT output is generated by the [`Display`] implementation. By default, nodes are
+/// labelled with their [`Debug`] representation; use [`node_label`](Self::node_label) to
+/// customize this.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::dot::Dot;
+///
+/// let dot = Dot::from_edges([('a', 'b'), ('b', 'c'), ('a', 'c')])
+///     .node_label(|n| n.to_ascii_uppercase().to_string())
+///     .edge_label(|a, b| format!("{a}{b}"))
+///     .highlight_path(['a', 'b', 'c']);
+/// let expected = r#"digraph G {
+///     n0 [label="A", color=red, penwidth=2];
+///     n1 [label="B", color=red, penwidth=2];
+///     n2 [label="C", color=red, penwidth=2];
+///     n0 -> n1 [label="ab", color=red, penwidth=2];
+///     n1 -> n2 [label="bc", color=red, penwidth=2];
+///     n0 -> n2 [label="ac"];
+/// }
+/// "#;
+/// assert_eq!(expected, dot.to_string());
+/// ```
+pub struct Dot<'a, N> {
+    name: String,
+    directed: bool,
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<(usize, usize)>,
+    node_label: Option<NodeLabel<'a, N>>,
+    edge_label: Option<EdgeLabel<'a, N>>,
+    highlighted_nodes: HashSet<usize>,
+    highlighted_edges: HashSet<(usize, usize)>,
+}
+
+type NodeLabel<'a, N> = Box<dyn Fn(&N) -> String + 'a>;
+type EdgeLabel<'a, N> = Box<dyn Fn(&N, &N) -> String + 'a>;
+
+impl<'a, N> Dot<'a, N>
+where
+    N: Clone + Eq + Hash,
+{
+    /// Creates an export of a [`Graph`] containing the given `nodes`, along with
+    /// all edges going out of them.
+    pub fn from_graph<G, C, I>(graph: &G, nodes: I) -> Self
+    where
+        G: Graph<N, C>,
+        C: Cost,
+        I: IntoIterator<Item = N>,
+    {
+        let mut dot = Self::new();
+        for node in nodes {
+            let from = dot.index_or_insert(node.clone());
+            for neighbour in graph.neighbours(&node) {
+                let to = dot.index_or_insert(neighbour);
+                dot.edges.push((from, to));
+            }
+        }
+        dot
+    }
+
+    /// Creates an export of a graph containing the given `(from, to)` edges.
+    ///
+    /// Can be used to export a search tree, like the `prev` map of a [Dijkstra output].
+    ///
+    /// [Dijkstra output]: crate::mapping::dij::Output
+    pub fn from_edges<I>(edges: I) -> Self
+    where
+        I: IntoIterator<Item = (N, N)>,
+    {
+        let mut dot = Self::new();
+        for (from, to) in edges {
+            let edge = (dot.index_or_insert(from), dot.index_or_insert(to));
+            dot.edges.push(edge);
+        }
+        dot
+    }
+
+    /// Sets the name of the exported graph (defaults to `G`).
+    pub fn name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.name = name.into();
+        self
+    }
+
+    /// Exports the graph as an undirected graph.
+    ///
+    /// Edges present in both directions will only be exported once.
+    pub fn undirected(mut self) -> Self {
+        self.directed = false;
+        self
+    }
+
+    /// Uses the given closure to generate node labels.
+    pub fn node_label<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&N) -> String + 'a,
+    {
+        self.node_label = Some(Box::new(f));
+        self
+    }
+
+    /// Uses the given closure to generate edge labels.
+    pub fn edge_label<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&N, &N) -> String + 'a,
+    {
+        self.edge_label = Some(Box::new(f));
+        self
+    }
+
+    /// Highlights the nodes of the given path, as well as the edges between them.
+    ///
+    /// Can be used with the output of [`assemble_path`](crate::mapping::dij::assemble_path).
+    /// Nodes or edges of the path that are not in the graph are ignored.
+    pub fn highlight_path<I>(mut self, path: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let path = path
+            .into_iter()
+            .filter_map(|n| self.indices.get(&n).copied())
+            .collect_vec();
+        self.highlighted_nodes.extend(path.iter().copied());
+        self.highlighted_edges
+            .extend(path.into_iter().tuple_windows::<(_, _)>());
+        self
+    }
+
+    /// Saves the DOT output to a file.
+    pub fn save<P>(&self, path: P) -> crate::Result<()>
+    where
+        P: AsRef<Path>,
+        N: Debug,
+    {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write DOT output to {}", path.display()))
+    }
+
+    fn new() -> Self {
+        Self {
+            name: "G".into(),
+            directed: true,
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+            node_label: None,
+            edge_label: None,
+            highlighted_nodes: HashSet::new(),
+            highlighted_edges: HashSet::new(),
+        }
+    }
+
+    fn index_or_insert(&mut self, node: N) -> usize {
+        *self.indices.entry(node).or_insert_with_key(|node| {
+            self.nodes.push(node.clone());
+            self.nodes.len() - 1
+        })
+    }
+
+    fn is_highlighted(&self, (a, b): (usize, usize)) -> bool {
+        self.highlighted_edges.contains(&(a, b))
+            || (!self.directed && self.highlighted_edges.contains(&(b, a)))
+    }
+}
+
+impl<N> From<&DiGraph<N>> for Dot<'_, N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn from(value: &DiGraph<N>) -> Self {
+        let mut dot = Self::from_edges(value.edges().map(|(a, b)| (a.clone(), b.clone())));
+        value.nodes().for_each(|n| {
+            dot.index_or_insert(n.clone());
+        });
+        dot
+    }
+}
+
+impl<N> Display for Dot<'_, N>
+where
+    N: Clone + Eq + Hash + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const HIGHLIGHT: &str = "color=red, penwidth=2";
+
+        let (kind, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        writeln!(f, "{kind} {} {{", quote_id(&self.name))?;
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let label = match &self.node_label {
+                Some(node_label) => node_label(node),
+                None => format!("{node:?}"),
+            };
+            write!(f, "    n{i} [label=\"{}\"", escape(&label))?;
+            if self.highlighted_nodes.contains(&i) {
+                write!(f, ", {HIGHLIGHT}")?;
+            }
+            writeln!(f, "];")?;
+        }
+
+        let mut seen = HashSet::new();
+        for &(a, b) in &self.edges {
+            if !seen.insert((a, b)) || (!self.directed && a != b && seen.contains(&(b, a))) {
+                continue;
+            }
+
+            let mut attributes = Vec::new();
+            if let Some(edge_label) = &self.edge_label {
+                let label = edge_label(&self.nodes[a], &self.nodes[b]);
+                attributes.push(format!("label=\"{}\"", escape(&label)));
+            }
+            if self.is_highlighted((a, b)) {
+                attributes.push(HIGHLIGHT.into());
+            }
+
+            write!(f, "    n{a} {arrow} n{b}")?;
+            if !attributes.is_empty() {
+                write!(f, " [{}]", attributes.join(", "))?;
+            }
+            writeln!(f, ";")?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// DOT keywords, which cannot be used as unquoted identifiers (regardless of case).
+const KEYWORDS: [&str; 6] = ["graph", "node", "edge", "digraph", "subgraph", "strict"];
+
+/// Quotes `id` unless it is a valid unquoted DOT identifier: alphanumeric characters and
+/// underscores, not starting with a digit and not a [keyword](KEYWORDS).
+fn quote_id(id: &str) -> String {
+    let unquoted = id.starts_with(|c: char| !c.is_ascii_digit())
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.iter().any(|k| id.eq_ignore_ascii_case(k));
+    match unquoted {
+        true => id.to_string(),
+        false => format!("\"{}\"", escape(id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::dij;
+
+    struct Line(usize);
+
+    impl Graph<usize> for Line {
+        fn neighbours(&self, node: &usize) -> impl Iterator<Item = usize> {
+            [node.checked_sub(1), Some(node + 1).filter(|n| *n < self.0)]
+                .into_iter()
+                .flatten()
+        }
+    }
+
+    #[test]
+    fn test_from_graph() {
+        let dot = Dot::from_graph(&Line(3), 0..3).name("my line").undirected();
+        let expected = r#"graph "my line" {
+    n0 [label="0"];
+    n1 [label="1"];
+    n2 [label="2"];
+    n0 -- n1;
+    n1 -- n2;
+}
+"#;
+        assert_eq!(expected, dot.to_string());
+
+        let output = dij::build(&Line(3), 0);
+        let path = dij::assemble_path(&output.prev, &0, &2);
+        let dot = Dot::from_graph(&Line(3), [1]).highlight_path(path);
+        let expected = r#"digraph G {
+    n0 [label="1", color=red, penwidth=2];
+    n1 [label="0", color=red, penwidth=2];
+    n2 [label="2", color=red, penwidth=2];
+    n0 -> n1;
+    n0 -> n2 [color=red, penwidth=2];
+}
+"#;
+        assert_eq!(expected, dot.to_string());
+
+        let dot = Dot::from_graph(&Line(1), [0]).name("2017");
+        assert!(dot.to_string().starts_with("digraph \"2017\" {\n"));
+        let dot = Dot::from_graph(&Line(1), [0]).name("day_12");
+        assert!(dot.to_string().starts_with("digraph day_12 {\n"));
+        let dot = Dot::from_graph(&Line(1), [0]).name("Graph");
+        assert!(dot.to_string().starts_with("digraph \"Graph\" {\n"));
+        let dot = Dot::from_graph(&Line(1), [0]).name("STRICT");
+        assert!(dot.to_string().starts_with("digraph \"STRICT\" {\n"));
+        let dot = Dot::from_graph(&Line(1), [0]).name("nodes");
+        assert!(dot.to_string().starts_with("digraph nodes {\n"));
+    }
+
+    #[test]
+    fn test_from_digraph() {
+        let mut graph = DiGraph::from_edges([("svr".to_string(), "out".to_string())]);
+        graph.add_node("lonely \"node\"".to_string());
+        let dot = Dot::from(&graph);
+        let expected = r#"digraph G {
+    n0 [label="\"svr\""];
+    n1 [label="\"out\""];
+    n2 [label="\"lonely \\\"node\\\"\""];
+    n0 -> n1;
+}
+"#;
+        assert_eq!(expected, dot.to_string());
+
+        let dot = Dot::from(&graph).node_label(|n| n.clone());
+        assert!(dot.to_string().contains(r#"n2 [label="lonely \"node\""];"#));
+
+        let dot = Dot::from_edges([(1, 1), (1, 2), (2, 1)]).undirected();
+        assert!(
+            dot.to_string()
+                .ends_with("    n0 -- n0;\n    n0 -- n1;\n}\n")
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Sub;
+
+use crate::mapping::dij::{Cost, Graph};
+
+/// Trait implemented by types that can be used as edge capacities in a [`FlowNetwork`].
+pub trait Capacity: Cost + Sub<Output = Self> {}
+
+impl<C> Capacity for C where C: Cost + Sub<Output = C> {}
+
+/// Directed graph with edge capacities, used to compute maximum flows.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use aoclp::mapping::flow::FlowNetwork;
+///
+/// let network: FlowNetwork<_> =
+///     [('s', 'a', 3), ('s', 'b', 2), ('a', 'b', 1), ('a', 't', 2), ('b', 't', 3)]
+///         .into_iter()
+///         .collect();
+/// let flow = network.max_flow(&'s', &'t');
+/// assert_eq!(5, flow.value);
+/// assert_eq!(HashSet::from(['s']), flow.cut.left);
+/// assert_eq!(vec![('s', 'a', 3), ('s', 'b', 2)], flow.cut.edges);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FlowNetwork<N, C = i64> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<FlowEdge<C>>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+/// Result of [`FlowNetwork::max_flow`].
+#[derive(Debug, Clone)]
+pub struct MaxFlow<N, C = i64> {
+    /// Value of the maximum flow.
+    pub value: C,
+    /// Edges that carry flow, with the amount of flow going through them.
+    pub flows: Vec<(N, N, C)>,
+    /// Minimum cut separating the source (on the [left](Cut::left)) from the sink.
+    pub cut: Cut<N, C>,
+}
+
+/// Cut separating the nodes of a graph in two partitions.
+#[derive(Debug, Clone)]
+pub struct Cut<N, C = i64> {
+    /// Total capacity of the cut edges.
+    pub value: C,
+    /// Edges going from the left partition to the right partition, with their capacity.
+    pub edges: Vec<(N, N, C)>,
+    /// Left partition.
+    pub left: HashSet<N>,
+    /// Right partition.
+    pub right: HashSet<N>,
+}
+
+#[derive(Debug, Clone)]
+struct FlowEdge<C> {
+    to: usize,
+    capacity: C,
+    residual: C,
+}
+
+impl<N, C> FlowNetwork<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Capacity,
+{
+    /// Creates a new, empty network.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+            adjacency: Vec::new(),
+        }
+    }
+
+    /// Creates a network by exploring a [`Graph`] from `start`, using the
+    /// [distance](Graph::dist) between nodes as capacity.
+    pub fn from_graph<G>(graph: &G, start: N) -> Self
+    where
+        G: Graph<N, C>,
+    {
+        let mut network = Self::new();
+        let mut q = VecDeque::from([network.index_or_insert(start)]);
+        while let Some(u) = q.pop_front() {
+            let from = network.nodes[u].clone();
+            for to in graph.neighbours(&from) {
+                let len = network.len();
+                let capacity = graph.dist(&from, &to);
+                let (_, v) = network.insert_edge(from.clone(), to, capacity);
+                if v == len {
+                    q.push_back(v);
+                }
+            }
+        }
+        network
+    }
+
+    /// Adds a directed edge with the given capacity, adding its nodes if needed.
+    pub fn add_edge(&mut self, from: N, to: N, capacity: C) {
+        self.insert_edge(from, to, capacity);
+    }
+
+    /// Adds an undirected edge, i.e. two directed edges with the given capacity.
+    pub fn add_undirected_edge(&mut self, a: N, b: N, capacity: C) {
+        self.add_edge(a.clone(), b.clone(), capacity);
+        self.add_edge(b, a, capacity);
+    }
+
+    /// Returns the number of nodes in the network.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the network has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Computes the maximum flow that can go from `source` to `sink`, using
+    /// [Dinic's algorithm](https://en.wikipedia.org/wiki/Dinic%27s_algorithm).
+    ///
+    /// The returned [`MaxFlow`] also includes a minimum cut, as per the
+    /// [max-flow min-cut theorem](https://en.wikipedia.org/wiki/Max-flow_min-cut_theorem).
+    ///
+    /// # Panics
+    ///
+    /// If `source` or `sink` is not in the network, or if they are the same node.
+    pub fn max_flow(&self, source: &N, sink: &N) -> MaxFlow<N, C> {
+        let (s, t) = (self.indices[source], self.indices[sink]);
+        assert_ne!(s, t, "source and sink must be different");
+
+        let mut edges = self.edges.clone();
+        let mut value = C::zero();
+        while let Some(levels) = Self::levels(&self.adjacency, &edges, s, Some(t)) {
+            value = value + Self::blocking_flow(&self.adjacency, &mut edges, &levels, s, t);
+        }
+
+        let left = Self::levels(&self.adjacency, &edges, s, None).unwrap();
+        let left = left.iter().map(|l| *l != usize::MAX).collect::<Vec<_>>();
+        let cut_edges = self
+            .forward_edges()
+            .filter(|(u, v, _)| left[*u] && !left[*v])
+            .map(|(u, v, e)| (self.nodes[u].clone(), self.nodes[v].clone(), self.edges[e].capacity))
+            .collect();
+        let flows = self
+            .forward_edges()
+            .filter_map(|(u, v, e)| {
+                let flow = self.edges[e].capacity - edges[e].residual;
+                (flow > C::zero()).then(|| (self.nodes[u].clone(), self.nodes[v].clone(), flow))
+            })
+            .collect();
+        let (left, right) = partition(&self.nodes, &left);
+
+        MaxFlow { value, flows, cut: Cut { value, edges: cut_edges, left, right } }
+    }
+
+    fn index_or_insert(&mut self, node: N) -> usize {
+        *self.indices.entry(node).or_insert_with_key(|node| {
+            self.nodes.push(node.clone());
+            self.adjacency.push(Vec::new());
+            self.nodes.len() - 1
+        })
+    }
+
+    fn insert_edge(&mut self, from: N, to: N, capacity: C) -> (usize, usize) {
+        let (u, v) = (self.index_or_insert(from), self.index_or_insert(to));
+
+        // Each edge is immediately followed by its reverse edge, so that
+        // the reverse of edge `e` is always `e ^ 1`.
+        self.adjacency[u].push(self.edges.len());
+        self.edges
+            .push(FlowEdge { to: v, capacity, residual: capacity });
+        self.adjacency[v].push(self.edges.len());
+        self.edges
+            .push(FlowEdge { to: u, capacity: C::zero(), residual: C::zero() });
+        (u, v)
+    }
+
+    /// Returns `(from, to, edge id)` for every edge added to the network (excluding reverse edges).
+    fn forward_edges(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        (0..self.edges.len())
+            .step_by(2)
+            .map(|e| (self.edges[e ^ 1].to, self.edges[e].to, e))
+    }
+
+    /// Computes the BFS level of each node reachable from `s` in the residual graph.
+    /// Returns `None` if `t` is given but not reachable.
+    fn levels(
+        adjacency: &[Vec<usize>],
+        edges: &[FlowEdge<C>],
+        s: usize,
+        t: Option<usize>,
+    ) -> Option<Vec<usize>> {
+        let mut levels = vec![usize::MAX; adjacency.len()];
+        levels[s] = 0;
+        let mut q = VecDeque::from([s]);
+        while let Some(u) = q.pop_front() {
+            for &e in &adjacency[u] {
+                let v = edges[e].to;
+                if levels[v] == usize::MAX && edges[e].residual > C::zero() {
+                    levels[v] = levels[u] + 1;
+                    q.push_back(v);
+                }
+            }
+        }
+
+        t.is_none_or(|t| levels[t] != usize::MAX).then_some(levels)
+    }
+
+    /// Saturates all shortest augmenting paths in the level graph and returns the
+    /// amount of flow that was pushed.
+    fn blocking_flow(
+        adjacency: &[Vec<usize>],
+        edges: &mut [FlowEdge<C>],
+        levels: &[usize],
+        s: usize,
+        t: usize,
+    ) -> C {
+        let mut next_edge = vec![0; adjacency.len()];
+        let mut dead = vec![false; adjacency.len()];
+        let mut total = C::zero();
+
+        loop {
+            let mut path: Vec<usize> = Vec::new();
+            let mut u = s;
+            while u != t {
+                let next = adjacency[u][next_edge[u]..].iter().position(|&e| {
+                    let v = edges[e].to;
+                    edges[e].residual > C::zero() && levels[v] == levels[u] + 1 && !dead[v]
+                });
+                match next {
+                    Some(offset) => {
+                        next_edge[u] += offset;
+                        let e = adjacency[u][next_edge[u]];
+                        path.push(e);
+                        u = edges[e].to;
+                    },
+                    None => {
+                        dead[u] = true;
+                        match path.pop() {
+                            Some(e) => {
+                                u = edges[e ^ 1].to;
+                                next_edge[u] += 1;
+                            },
+                            None => return total,
+                        }
+                    },
+                }
+            }
+
+            let bottleneck = path.iter().map(|e| edges[*e].residual).min().unwrap();
+            for e in path {
+                edges[e].residual = edges[e].residual - bottleneck;
+                edges[e ^ 1].residual = edges[e ^ 1].residual + bottleneck;
+            }
+            total = total + bottleneck;
+        }
+    }
+}
+
+impl<N, C> Default for FlowNetwork<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Capacity,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, C> FromIterator<(N, N, C)> for FlowNetwork<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Capacity,
+{
+    fn from_iter<I: IntoIterator<Item = (N, N, C)>>(iter: I) -> Self {
+        let mut network = Self::new();
+        for (from, to, capacity) in iter {
+            network.add_edge(from, to, capacity);
+        }
+        network
+    }
+}
+
+/// Computes the global minimum cut of an undirected graph given as a list of weighted
+/// edges, using the [Stoer-Wagner algorithm](https://en.wikipedia.org/wiki/Stoer%E2%80%93Wagner_algorithm).
+///
+/// Returns `None` if the graph has less than two nodes.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::flow::stoer_wagner;
+///
+/// // Two triangles linked by a single edge
+/// let edges = [(1, 2, 1), (2, 3, 1), (3, 1, 1), (4, 5, 1), (5, 6, 1), (6, 4, 1), (3, 4, 1)];
+/// let cut = stoer_wagner(edges).unwrap();
+/// assert_eq!(1, cut.value);
+/// assert_eq!(3, cut.left.len());
+/// assert_eq!(3, cut.right.len());
+/// ```
+pub fn stoer_wagner<N, C, I>(edges: I) -> Option<Cut<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Capacity,
+    I: IntoIterator<Item = (N, N, C)>,
+{
+    let mut nodes = Vec::new();
+    let mut indices = HashMap::new();
+    let mut index_of = |node: N| {
+        *indices.entry(node).or_insert_with_key(|node| {
+            nodes.push(node.clone());
+            nodes.len() - 1
+        })
+    };
+    let edges: Vec<_> = edges
+        .into_iter()
+        .map(|(a, b, c)| (index_of(a), index_of(b), c))
+        .collect();
+    if nodes.len() < 2 {
+        return None;
+    }
+
+    let mut adjacency: Vec<HashMap<usize, C>> = vec![HashMap::new(); nodes.len()];
+    for &(a, b, c) in edges.iter().filter(|(a, b, _)| a != b) {
+        add_weight(&mut adjacency[a], b, c);
+        add_weight(&mut adjacency[b], a, c);
+    }
+
+    let mut groups: Vec<Vec<usize>> = (0..nodes.len()).map(|i| vec![i]).collect();
+    let mut active: Vec<usize> = (0..nodes.len()).collect();
+    let mut best: Option<(C, Vec<usize>)> = None;
+
+    while active.len() > 1 {
+        // Maximum adjacency search: repeatedly add the node most tightly connected to the
+        // nodes added so far. The last two nodes added are then merged together.
+        let mut weights = vec![C::zero(); nodes.len()];
+        let mut added = vec![false; nodes.len()];
+        let mut q: BinaryHeap<_> = active.iter().map(|&v| (C::zero(), v)).collect();
+        let (mut prev, mut last) = (usize::MAX, usize::MAX);
+        while let Some((w, v)) = q.pop() {
+            if added[v] || w != weights[v] {
+                continue;
+            }
+            added[v] = true;
+            (prev, last) = (last, v);
+            for (&u, &c) in &adjacency[v] {
+                if !added[u] {
+                    weights[u] = weights[u] + c;
+                    q.push((weights[u], u));
+                }
+            }
+        }
+
+        if best
+            .as_ref()
+            .is_none_or(|(value, _)| weights[last] < *value)
+        {
+            best = Some((weights[last], groups[last].clone()));
+        }
+
+        let last_adjacency = std::mem::take(&mut adjacency[last]);
+        for (u, c) in last_adjacency {
+            adjacency[u].remove(&last);
+            if u != prev {
+                let merged = add_weight(&mut adjacency[prev], u, c);
+                adjacency[u].insert(prev, merged);
+            }
+        }
+        let last_group = std::mem::take(&mut groups[last]);
+        groups[prev].extend(last_group);
+        active.retain(|v| *v != last);
+    }
+
+    let (value, left_indices) = best.unwrap();
+    let mut is_left = vec![false; nodes.len()];
+    left_indices.iter().for_each(|i| is_left[*i] = true);
+    let cut_edges = edges
+        .into_iter()
+        .filter(|(a, b, _)| is_left[*a] != is_left[*b])
+        .map(|(a, b, c)| match is_left[a] {
+            true => (nodes[a].clone(), nodes[b].clone(), c),
+            false => (nodes[b].clone(), nodes[a].clone(), c),
+        })
+        .collect();
+    let (left, right) = partition(&nodes, &is_left);
+
+    Some(Cut { value, edges: cut_edges, left, right })
+}
+
+fn add_weight<C>(weights: &mut HashMap<usize, C>, to: usize, weight: C) -> C
+where
+    C: Capacity,
+{
+    let weight = weights.get(&to).copied().unwrap_or_else(C::zero) + weight;
+    weights.insert(to, weight);
+    weight
+}
+
+fn partition<N>(nodes: &[N], is_left: &[bool]) -> (HashSet<N>, HashSet<N>)
+where
+    N: Clone + Eq + Hash,
+{
+    let (left, right): (Vec<_>, Vec<_>) = nodes.iter().zip(is_left).partition(|(_, l)| **l);
+    let into_set = |v: Vec<(&N, &bool)>| v.into_iter().map(|(n, _)| n.clone()).collect();
+    (into_set(left), into_set(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::mapping::canvas::dynamic::Canvas;
+    use crate::mapping::grid::{GridGraph, Neighbourhood};
+    use crate::positioning::pt::Pt;
+
+    /// Classic example from CLRS: max flow of 23 from `s` to `t`.
+    fn clrs() -> FlowNetwork<&'static str> {
+        [
+            ("s", "v1", 16),
+            ("s", "v2", 13),
+            ("v1", "v3", 12),
+            ("v2", "v1", 4),
+            ("v2", "v4", 14),
+            ("v3", "v2", 9),
+            ("v3", "t", 20),
+            ("v4", "v3", 7),
+            ("v4", "t", 4),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_max_flow() {
+        let flow = clrs().max_flow(&"s", &"t");
+        assert_eq!(23, flow.value);
+        assert_eq!(23, flow.cut.value);
+        assert_eq!(23, flow.cut.edges.iter().map(|(_, _, c)| c).sum::<i64>());
+        assert_eq!(HashSet::from(["s", "v1", "v2", "v4"]), flow.cut.left);
+        assert_eq!(HashSet::from(["v3", "t"]), flow.cut.right);
+        assert_eq!(
+            vec![("v1", "v3", 12), ("v4", "t", 4), ("v4", "v3", 7)],
+            flow.cut.edges.into_iter().sorted().collect_vec()
+        );
+
+        let out_of = |n| {
+            flow.flows
+                .iter()
+                .filter(|(u, _, _)| *u == n)
+                .map(|(_, _, f)| f)
+                .sum::<i64>()
+        };
+        let into = |n| {
+            flow.flows
+                .iter()
+                .filter(|(_, v, _)| *v == n)
+                .map(|(_, _, f)| f)
+                .sum::<i64>()
+        };
+        assert_eq!(23, out_of("s"));
+        assert_eq!(23, into("t"));
+        for n in ["v1", "v2", "v3", "v4"] {
+            assert_eq!(into(n), out_of(n));
+        }
+    }
+
+    #[test]
+    fn test_max_flow_from_graph() {
+        // Number of edge-disjoint paths between opposite corners
+        let canvas = Canvas::from_lines(["....", ".#..", "...."], |c| c);
+        let graph = GridGraph::new(&canvas, Neighbourhood::Four, |_: &char, to: &char| *to != '#');
+        let network = FlowNetwork::<_, usize>::from_graph(&graph, Pt::new(0, 0));
+        assert_eq!(11, network.len());
+        let flow = network.max_flow(&Pt::new(0, 0), &Pt::new(3, 2));
+        assert_eq!(2, flow.value);
+
+        let network: FlowNetwork<_> = [(1, 2, 5)].into_iter().collect();
+        let flow = network.max_flow(&2, &1);
+        assert_eq!(0, flow.value);
+        assert!(flow.cut.edges.is_empty());
+        assert_eq!(HashSet::from([2]), flow.cut.left);
+    }
+
+    #[test]
+    fn test_stoer_wagner() {
+        // Example from the original paper: min cut of 4 between {3, 4, 7, 8} and the rest
+        let edges = [
+            (1, 2, 2),
+            (1, 5, 3),
+            (2, 3, 3),
+            (2, 5, 2),
+            (2, 6, 2),
+            (3, 4, 4),
+            (3, 7, 2),
+            (4, 7, 2),
+            (4, 8, 2),
+            (5, 6, 3),
+            (6, 7, 1),
+            (7, 8, 3),
+        ];
+        let cut = stoer_wagner(edges).unwrap();
+        assert_eq!(4, cut.value);
+        let side = if cut.left.contains(&3) { &cut.left } else { &cut.right };
+        assert_eq!(&HashSet::from([3, 4, 7, 8]), side);
+        assert_eq!(2, cut.edges.len());
+        assert!(
+            cut.edges
+                .iter()
+                .all(|(a, b, _)| cut.left.contains(a) && cut.right.contains(b))
+        );
+
+        let cut = stoer_wagner([(1, 2, 1), (3, 4, 1)]).unwrap();
+        assert_eq!(0, cut.value);
+        assert!(cut.edges.is_empty());
+
+        assert!(stoer_wagner([(1, 1, 1)]).is_none());
+    }
+}
@@ -0,0 +1,533 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+
+use itertools::Itertools;
+
+use crate::mapping::dij::Graph;
+use crate::num::{BigUint, One, Zero};
+
+/// Directed graph stored as adjacency lists.
+///
+/// Nodes are kept in insertion order, so algorithms returning nodes are deterministic.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::graph::DiGraph;
+/// # use aoclp::num::BigUint;
+///
+/// let graph: DiGraph<_> = [('a', 'b'), ('b', 'c'), ('a', 'c')].into_iter().collect();
+/// assert_eq!(vec!['a', 'b', 'c'], graph.toposort().unwrap());
+/// assert_eq!(BigUint::from(2u32), graph.count_paths(&'a', &'c').unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiGraph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<Vec<usize>>,
+}
+
+impl<N> DiGraph<N>
+where
+    N: Clone + Eq + Hash,
+{
+    /// Creates a new, empty graph.
+    pub fn new() -> Self {
+        Self { nodes: Vec::new(), indices: HashMap::new(), edges: Vec::new() }
+    }
+
+    /// Creates a graph from an [iterator](IntoIterator) of `(from, to)` edges.
+    pub fn from_edges<I>(edges: I) -> Self
+    where
+        I: IntoIterator<Item = (N, N)>,
+    {
+        let mut graph = Self::new();
+        graph.extend(edges);
+        graph
+    }
+
+    /// Creates a graph from an [iterator](IntoIterator) of nodes and their successors.
+    ///
+    /// Every node listed is added to the graph, even if it has no successors.
+    pub fn from_adjacency<I, S>(adjacency: I) -> Self
+    where
+        I: IntoIterator<Item = (N, S)>,
+        S: IntoIterator<Item = N>,
+    {
+        let mut graph = Self::new();
+        for (from, successors) in adjacency {
+            graph.add_node(from.clone());
+            for to in successors {
+                graph.add_edge(from.clone(), to);
+            }
+        }
+        graph
+    }
+
+    /// Adds a node to the graph, if it's not already present.
+    pub fn add_node(&mut self, node: N) {
+        self.index_or_insert(node);
+    }
+
+    /// Adds an edge to the graph, adding its nodes if needed.
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let (from, to) = (self.index_or_insert(from), self.index_or_insert(to));
+        self.edges[from].push(to);
+    }
+
+    /// Returns the number of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns `true` if the graph contains the given node.
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    /// Returns an [iterator](Iterator) of the graph's nodes, in insertion order.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter()
+    }
+
+    /// Returns an [iterator](Iterator) of the graph's edges.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        self.edges.iter().enumerate().flat_map(move |(from, tos)| {
+            tos.iter()
+                .map(move |to| (&self.nodes[from], &self.nodes[*to]))
+        })
+    }
+
+    /// Returns an [iterator](Iterator) of the successors of `node`.
+    pub fn successors<'a>(&'a self, node: &N) -> impl Iterator<Item = &'a N> + use<'a, N> {
+        self.indices
+            .get(node)
+            .into_iter()
+            .flat_map(move |i| self.edges[*i].iter().map(move |j| &self.nodes[*j]))
+    }
+
+    /// Returns a copy of this graph with all edges reversed.
+    pub fn reversed(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            indices: self.indices.clone(),
+            edges: self.reverse_edges(),
+        }
+    }
+
+    /// Returns the nodes of the graph sorted in topological order, or the first
+    /// [`Cycle`] found if the graph is not a DAG.
+    pub fn toposort(&self) -> Result<Vec<N>, Cycle<N>> {
+        self.toposort_indices(0..self.len())
+            .map(|order| order.into_iter().map(|i| self.nodes[i].clone()).collect())
+    }
+
+    /// Returns the strongly connected components of the graph, computed using
+    /// [Tarjan's algorithm](https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm).
+    ///
+    /// Components are returned in reverse topological order: no component has an
+    /// edge to a component that comes after it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let mut index = vec![UNVISITED; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for root in 0..self.len() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+
+            let mut work = vec![(root, 0)];
+            while let Some(&(v, i)) = work.last() {
+                if index[v] == UNVISITED {
+                    index[v] = next_index;
+                    low[v] = next_index;
+                    next_index += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                }
+
+                if let Some(&w) = self.edges[v].get(i) {
+                    work.last_mut().unwrap().1 += 1;
+                    if index[w] == UNVISITED {
+                        work.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some(&(u, _)) = work.last() {
+                    low[u] = low[u].min(low[v]);
+                }
+                if low[v] == index[v] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack[w] = false;
+                        component.push(self.nodes[w].clone());
+                        if w == v {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Returns the weakly connected components of the graph, i.e. the groups of nodes
+    /// that are connected when ignoring the direction of edges.
+    pub fn weakly_connected_components(&self) -> Vec<Vec<N>> {
+        let reverse_edges = self.reverse_edges();
+        let mut seen = vec![false; self.len()];
+
+        (0..self.len())
+            .filter_map(|root| {
+                if seen[root] {
+                    return None;
+                }
+
+                let component = Self::bfs(root, &mut seen, |v| {
+                    self.edges[v].iter().chain(reverse_edges[v].iter()).copied()
+                });
+                Some(
+                    component
+                        .into_iter()
+                        .map(|i| self.nodes[i].clone())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the set of nodes that can be reached from `node`, including `node` itself.
+    pub fn reachable_from(&self, node: &N) -> HashSet<N> {
+        self.reachable_with(node, &self.edges)
+    }
+
+    /// Returns the set of nodes from which `node` can be reached, including `node` itself.
+    pub fn reaching(&self, node: &N) -> HashSet<N> {
+        self.reachable_with(node, &self.reverse_edges())
+    }
+
+    /// Returns the number of distinct paths going from `from` to `to`.
+    ///
+    /// Returns an error if a [`Cycle`] can be reached from `from`, since there
+    /// could then be an infinite number of paths.
+    pub fn count_paths(&self, from: &N, to: &N) -> Result<BigUint, Cycle<N>> {
+        self.count_paths_via(from, to, &[])
+    }
+
+    /// Returns the number of distinct paths going from `from` to `to` that pass
+    /// through all `waypoints`, in any order.
+    ///
+    /// Returns an error if a [`Cycle`] can be reached from `from`, since there
+    /// could then be an infinite number of paths.
+    pub fn count_paths_via(&self, from: &N, to: &N, waypoints: &[N]) -> Result<BigUint, Cycle<N>> {
+        let Some(&from) = self.indices.get(from) else {
+            return Ok(BigUint::zero());
+        };
+
+        // In a DAG, a path can only go through waypoints in topological order, so
+        // we can count paths between each pair of consecutive waypoints.
+        let order = self.toposort_indices([from])?;
+        let positions: HashMap<_, _> = order.iter().enumerate().map(|(p, i)| (*i, p)).collect();
+        let Some(stops) = waypoints
+            .iter()
+            .chain([to])
+            .map(|n| self.indices.get(n).and_then(|i| positions.get(i)).copied())
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(BigUint::zero());
+        };
+        let (to, waypoints) = stops.split_last().unwrap();
+
+        Ok([0]
+            .into_iter()
+            .chain(waypoints.iter().copied().sorted())
+            .chain([*to])
+            .tuple_windows()
+            .map(|(a, b)| self.count_paths_in_order(&order, a, b))
+            .product())
+    }
+
+    fn index_or_insert(&mut self, node: N) -> usize {
+        *self.indices.entry(node).or_insert_with_key(|node| {
+            self.nodes.push(node.clone());
+            self.edges.push(Vec::new());
+            self.nodes.len() - 1
+        })
+    }
+
+    fn reverse_edges(&self) -> Vec<Vec<usize>> {
+        let mut reverse_edges = vec![Vec::new(); self.len()];
+        for (from, tos) in self.edges.iter().enumerate() {
+            for to in tos {
+                reverse_edges[*to].push(from);
+            }
+        }
+        reverse_edges
+    }
+
+    fn reachable_with(&self, node: &N, edges: &[Vec<usize>]) -> HashSet<N> {
+        let Some(&start) = self.indices.get(node) else {
+            return HashSet::new();
+        };
+
+        let mut seen = vec![false; self.len()];
+        Self::bfs(start, &mut seen, |v| edges[v].iter().copied())
+            .into_iter()
+            .map(|i| self.nodes[i].clone())
+            .collect()
+    }
+
+    fn bfs<F, I>(start: usize, seen: &mut [bool], mut neighbours: F) -> Vec<usize>
+    where
+        F: FnMut(usize) -> I,
+        I: Iterator<Item = usize>,
+    {
+        let mut visited = vec![start];
+        let mut q = VecDeque::from([start]);
+        seen[start] = true;
+
+        while let Some(v) = q.pop_front() {
+            for w in neighbours(v) {
+                if !seen[w] {
+                    seen[w] = true;
+                    visited.push(w);
+                    q.push_back(w);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Topologically sorts the nodes reachable from `roots` using a depth-first search.
+    fn toposort_indices<R>(&self, roots: R) -> Result<Vec<usize>, Cycle<N>>
+    where
+        R: IntoIterator<Item = usize>,
+    {
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        enum State {
+            New,
+            Visiting,
+            Done,
+        }
+
+        let mut state = vec![State::New; self.len()];
+        let mut order = Vec::new();
+
+        for root in roots {
+            if state[root] != State::New {
+                continue;
+            }
+
+            state[root] = State::Visiting;
+            let mut work = vec![(root, 0)];
+            while let Some(&(v, i)) = work.last() {
+                match self.edges[v].get(i) {
+                    Some(&w) => {
+                        work.last_mut().unwrap().1 += 1;
+                        match state[w] {
+                            State::New => {
+                                state[w] = State::Visiting;
+                                work.push((w, 0));
+                            },
+                            State::Visiting => {
+                                let cycle = work
+                                    .iter()
+                                    .skip_while(|(u, _)| *u != w)
+                                    .map(|(u, _)| self.nodes[*u].clone())
+                                    .collect();
+                                return Err(Cycle(cycle));
+                            },
+                            State::Done => (),
+                        }
+                    },
+                    None => {
+                        work.pop();
+                        state[v] = State::Done;
+                        order.push(v);
+                    },
+                }
+            }
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    /// Counts paths between the nodes at positions `from` and `to` in a topological `order`.
+    fn count_paths_in_order(&self, order: &[usize], from: usize, to: usize) -> BigUint {
+        if from > to {
+            return BigUint::zero();
+        }
+
+        let mut counts: HashMap<usize, BigUint> = HashMap::from([(order[from], BigUint::one())]);
+        for &v in &order[from..to] {
+            if let Some(count) = counts.remove(&v) {
+                for w in &self.edges[v] {
+                    *counts.entry(*w).or_default() += &count;
+                }
+            }
+        }
+        counts.remove(&order[to]).unwrap_or_default()
+    }
+}
+
+impl<N> Default for DiGraph<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> Extend<(N, N)> for DiGraph<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn extend<I: IntoIterator<Item = (N, N)>>(&mut self, iter: I) {
+        for (from, to) in iter {
+            self.add_edge(from, to);
+        }
+    }
+}
+
+impl<N> FromIterator<(N, N)> for DiGraph<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(iter: I) -> Self {
+        Self::from_edges(iter)
+    }
+}
+
+impl<N> Graph<N> for DiGraph<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = N> {
+        self.successors(node).cloned()
+    }
+}
+
+/// Cycle found in a [`DiGraph`] that was expected to be acyclic.
+///
+/// Contains the nodes of the cycle in order; the last node has an edge back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N> Display for Cycle<N>
+where
+    N: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle found in graph: {:?}", self.0)
+    }
+}
+
+impl<N> Error for Cycle<N> where N: Debug {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::dij;
+
+    /// `a -> b -> c -> a` cycle, feeding into `d -> e`, plus isolated `f -> g`.
+    fn cyclic() -> DiGraph<char> {
+        "ab bc ca cd de fg"
+            .split(' ')
+            .map(|e| e.chars().collect_tuple().unwrap())
+            .collect()
+    }
+
+    /// Diamond-shaped DAG going through `x` or `y` twice: `s -> {a, b} -> m -> {x, y} -> t`.
+    fn diamonds() -> DiGraph<char> {
+        DiGraph::from_adjacency([
+            ('s', vec!['a', 'b']),
+            ('a', vec!['m']),
+            ('b', vec!['m']),
+            ('m', vec!['x', 'y']),
+            ('x', vec!['t']),
+            ('y', vec!['t']),
+            ('t', vec![]),
+            ('z', vec![]),
+        ])
+    }
+
+    #[test]
+    fn test_toposort() {
+        let order = diamonds().toposort().unwrap();
+        let position = |n| order.iter().position(|o| *o == n).unwrap();
+        assert_eq!(8, order.len());
+        assert!(diamonds().edges().all(|(a, b)| position(*a) < position(*b)));
+
+        let cycle = cyclic().toposort().unwrap_err();
+        assert_eq!(vec!['a', 'b', 'c'], cycle.0);
+        assert_eq!("cycle found in graph: ['a', 'b', 'c']", cycle.to_string());
+
+        let self_loop: DiGraph<_> = [(1, 2), (2, 2)].into_iter().collect();
+        assert_eq!(Cycle(vec![2]), self_loop.toposort().unwrap_err());
+    }
+
+    #[test]
+    fn test_components() {
+        let sccs = cyclic()
+            .strongly_connected_components()
+            .into_iter()
+            .map(|c| c.into_iter().sorted().collect::<String>())
+            .collect_vec();
+        assert_eq!(vec!["e", "d", "abc", "g", "f"], sccs);
+
+        let wccs = cyclic().weakly_connected_components();
+        assert_eq!(vec![vec!['a', 'b', 'c', 'd', 'e'], vec!['f', 'g']], wccs);
+        assert_eq!(2, diamonds().weakly_connected_components().len());
+    }
+
+    #[test]
+    fn test_reachability() {
+        assert_eq!(HashSet::from(['c', 'a', 'b', 'd', 'e']), cyclic().reachable_from(&'c'));
+        assert_eq!(HashSet::from(['d', 'a', 'b', 'c']), cyclic().reaching(&'d'));
+        assert_eq!(HashSet::from(['g']), cyclic().reachable_from(&'g'));
+        assert!(cyclic().reachable_from(&'z').is_empty());
+
+        let output = dij::build(&cyclic(), 'b');
+        assert_eq!(Some(&3), output.dist.get(&'e'));
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph = diamonds();
+        let count =
+            |from, to, waypoints: &[char]| graph.count_paths_via(&from, &to, waypoints).unwrap();
+        assert_eq!(BigUint::from(4u32), count('s', 't', &[]));
+        assert_eq!(BigUint::from(2u32), count('s', 't', &['x']));
+        assert_eq!(BigUint::from(1u32), count('s', 't', &['y', 'b']));
+        assert_eq!(BigUint::zero(), count('s', 't', &['x', 'y']));
+        assert_eq!(BigUint::zero(), count('s', 't', &['z']));
+        assert_eq!(BigUint::zero(), count('t', 's', &[]));
+        assert_eq!(BigUint::one(), count('m', 'm', &[]));
+
+        assert!(cyclic().count_paths(&'a', &'e').is_err());
+        assert_eq!(BigUint::one(), cyclic().count_paths(&'d', &'e').unwrap());
+    }
+}
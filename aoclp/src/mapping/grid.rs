@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Add;
+
+use itertools::Either;
+use num::{FromPrimitive, ToPrimitive};
+
+use crate::mapping::canvas::{dynamic, fixed};
+use crate::mapping::dij::{Cost, Graph};
+use crate::positioning::direction::eight_points::Direction8;
+use crate::positioning::direction::four_points::Direction4;
+use crate::positioning::direction::{Direction, MovementDirection};
+use crate::positioning::pt::Pt;
+
+/// Trait implemented by 2D grids whose cells can be looked up by [point](Pt).
+pub trait GridCells<P> {
+    /// Type of values stored in the grid's cells.
+    type Value;
+
+    /// Returns the value of the cell at the given point, or `None` if the point is
+    /// outside the grid.
+    fn cell(&self, pt: &P) -> Option<&Self::Value>;
+}
+
+impl<T, V> GridCells<Pt<T>> for dynamic::Canvas<V>
+where
+    T: ToPrimitive,
+{
+    type Value = V;
+
+    fn cell(&self, pt: &Pt<T>) -> Option<&Self::Value> {
+        self.row(pt.y.to_usize()?)?.get(pt.x.to_usize()?)
+    }
+}
+
+impl<T, V, const W: usize, const H: usize> GridCells<Pt<T>> for fixed::Canvas<V, W, H>
+where
+    T: ToPrimitive,
+{
+    type Value = V;
+
+    fn cell(&self, pt: &Pt<T>) -> Option<&Self::Value> {
+        self.0.get(pt.y.to_usize()?)?.get(pt.x.to_usize()?)
+    }
+}
+
+impl<T, V, S> GridCells<Pt<T>> for HashMap<Pt<T>, V, S>
+where
+    Pt<T>: Hash + Eq,
+    S: BuildHasher,
+{
+    type Value = V;
+
+    fn cell(&self, pt: &Pt<T>) -> Option<&Self::Value> {
+        self.get(pt)
+    }
+}
+
+/// Trait implemented by 2D [grids](GridCells) whose points can be enumerated.
+pub trait GridPoints<P>: GridCells<P> {
+    /// Returns an [iterator](Iterator) of all points in the grid.
+    ///
+    /// For canvases, points are returned in row-major order.
+    fn points(&self) -> impl Iterator<Item = P>;
+}
+
+impl<T, V> GridPoints<Pt<T>> for dynamic::Canvas<V>
+where
+    T: ToPrimitive + FromPrimitive,
+{
+    fn points(&self) -> impl Iterator<Item = Pt<T>> {
+        canvas_points(self.width(), self.height())
+    }
+}
+
+impl<T, V, const W: usize, const H: usize> GridPoints<Pt<T>> for fixed::Canvas<V, W, H>
+where
+    T: ToPrimitive + FromPrimitive,
+{
+    fn points(&self) -> impl Iterator<Item = Pt<T>> {
+        canvas_points(W, H)
+    }
+}
+
+impl<T, V, S> GridPoints<Pt<T>> for HashMap<Pt<T>, V, S>
+where
+    Pt<T>: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    fn points(&self) -> impl Iterator<Item = Pt<T>> {
+        self.keys().cloned()
+    }
+}
+
+fn canvas_points<T>(width: usize, height: usize) -> impl Iterator<Item = Pt<T>>
+where
+    T: FromPrimitive,
+{
+    (0..height).flat_map(move |y| {
+        (0..width).map(move |x| Pt::new(T::from_usize(x).unwrap(), T::from_usize(y).unwrap()))
+    })
+}
+
+/// Neighbours considered when moving around a [`GridGraph`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Neighbourhood {
+    /// Orthogonal neighbours only (see [`Pt::four_neighbours`]).
+    Four,
+    /// Orthogonal and diagonal neighbours (see [`Pt::eight_neighbours`]).
+    Eight,
+}
+
+impl Neighbourhood {
+    /// Returns the neighbours of `pt` in this neighbourhood.
+    pub fn of<T>(self, pt: Pt<T>) -> impl Iterator<Item = Pt<T>>
+    where
+        Pt<T>: Add<Output = Pt<T>> + Copy,
+        Direction4: MovementDirection<T>,
+        Direction8: MovementDirection<T>,
+    {
+        match self {
+            Self::Four => Either::Left(pt.four_neighbours()),
+            Self::Eight => Either::Right(pt.eight_neighbours()),
+        }
+    }
+}
+
+/// Adapter implementing [`Graph`] over a 2D grid, like a [canvas](dynamic::Canvas) or a
+/// map of [points](Pt).
+///
+/// Nodes of the graph can either be:
+///
+/// - [points](Pt): moving to any neighbour (as determined by the [`Neighbourhood`]) is allowed
+///   as long as the neighbour is in the grid and the `passable` predicate returns `true` for
+///   the `(from, to)` cells.
+/// - `(Pt, Direction4)` pairs: moving one step forward is allowed under the same conditions
+///   as above, and turning 90 degrees left or right in place is always allowed. Useful for
+///   puzzles where turning has a cost.
+///
+/// By default, every move costs 1. Use [`with_cost`](Self::with_cost) to compute edge
+/// costs with a closure instead.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::canvas::dynamic::Canvas;
+/// # use aoclp::mapping::dij;
+/// # use aoclp::mapping::grid::{GridGraph, Neighbourhood};
+/// # use aoclp::positioning::direction::four_points::Direction4;
+/// # use aoclp::positioning::pt::Pt;
+///
+/// let canvas = Canvas::from_lines(["...", ".#.", "..."], |c| c);
+/// let graph = GridGraph::new(&canvas, Neighbourhood::Four, |_: &char, to: &char| *to != '#');
+/// let output = dij::build(&graph, Pt::new(0, 0));
+/// assert_eq!(Some(&4), output.dist.get(&Pt::new(2, 2)));
+/// assert_eq!(None, output.dist.get(&Pt::new(1, 1)));
+///
+/// let graph = graph.with_cost(
+///     |a: &(Pt, Direction4), b: &(Pt, Direction4)| {
+///         if a.0 == b.0 { 1000 } else { 1 }
+///     },
+/// );
+/// let output = dij::build(&graph, (Pt::new(0, 0), Direction4::Right));
+/// assert_eq!(Some(&1004), output.dist.get(&(Pt::new(2, 2), Direction4::Down)));
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct GridGraph<'a, M, P, W = ()> {
+    cells: &'a M,
+    neighbourhood: Neighbourhood,
+    passable: P,
+    cost: W,
+}
+
+impl<'a, M, P> GridGraph<'a, M, P> {
+    /// Creates a new [`GridGraph`] over `cells`, with every move costing 1.
+    pub fn new(cells: &'a M, neighbourhood: Neighbourhood, passable: P) -> Self {
+        Self { cells, neighbourhood, passable, cost: () }
+    }
+}
+
+impl<'a, M, P, W> GridGraph<'a, M, P, W> {
+    /// Returns a [`GridGraph`] that uses the given closure to compute the cost of moving
+    /// from one node to another.
+    pub fn with_cost<NW>(self, cost: NW) -> GridGraph<'a, M, P, NW> {
+        GridGraph {
+            cells: self.cells,
+            neighbourhood: self.neighbourhood,
+            passable: self.passable,
+            cost,
+        }
+    }
+
+    /// Returns a reference to the grid's cells.
+    pub fn cells(&self) -> &'a M {
+        self.cells
+    }
+
+    fn can_move<T>(&self, from: &Pt<T>, to: &Pt<T>) -> bool
+    where
+        M: GridCells<Pt<T>>,
+        P: Fn(&M::Value, &M::Value) -> bool,
+    {
+        match (self.cells.cell(from), self.cells.cell(to)) {
+            (Some(from), Some(to)) => (self.passable)(from, to),
+            _ => false,
+        }
+    }
+
+    fn pt_neighbours<T>(&self, node: &Pt<T>) -> impl Iterator<Item = Pt<T>>
+    where
+        M: GridCells<Pt<T>>,
+        P: Fn(&M::Value, &M::Value) -> bool,
+        Pt<T>: Add<Output = Pt<T>> + Copy,
+        Direction4: MovementDirection<T>,
+        Direction8: MovementDirection<T>,
+    {
+        let node = *node;
+        self.neighbourhood
+            .of(node)
+            .filter(move |n| self.can_move(&node, n))
+    }
+
+    fn directed_neighbours<T>(
+        &self,
+        node: &(Pt<T>, Direction4),
+    ) -> impl Iterator<Item = (Pt<T>, Direction4)>
+    where
+        M: GridCells<Pt<T>>,
+        P: Fn(&M::Value, &M::Value) -> bool,
+        Pt<T>: Add<Output = Pt<T>> + Copy,
+        Direction4: MovementDirection<T>,
+    {
+        let (pt, dir) = *node;
+        let forward = pt + dir.displacement();
+        [dir.turn_left(), dir.turn_right()]
+            .into_iter()
+            .map(move |d| (pt, d))
+            .chain(self.can_move(&pt, &forward).then_some((forward, dir)))
+    }
+}
+
+impl<T, M, P> Graph<Pt<T>> for GridGraph<'_, M, P>
+where
+    M: GridCells<Pt<T>>,
+    P: Fn(&M::Value, &M::Value) -> bool,
+    Pt<T>: Add<Output = Pt<T>> + Copy,
+    Direction4: MovementDirection<T>,
+    Direction8: MovementDirection<T>,
+{
+    fn neighbours(&self, node: &Pt<T>) -> impl Iterator<Item = Pt<T>> {
+        self.pt_neighbours(node)
+    }
+}
+
+impl<T, M, P, W, C> Graph<Pt<T>, C> for GridGraph<'_, M, P, W>
+where
+    M: GridCells<Pt<T>>,
+    P: Fn(&M::Value, &M::Value) -> bool,
+    W: Fn(&Pt<T>, &Pt<T>) -> C,
+    C: Cost,
+    Pt<T>: Add<Output = Pt<T>> + Copy,
+    Direction4: MovementDirection<T>,
+    Direction8: MovementDirection<T>,
+{
+    fn neighbours(&self, node: &Pt<T>) -> impl Iterator<Item = Pt<T>> {
+        self.pt_neighbours(node)
+    }
+
+    fn dist(&self, a: &Pt<T>, b: &Pt<T>) -> C {
+        (self.cost)(a, b)
+    }
+}
+
+impl<T, M, P> Graph<(Pt<T>, Direction4)> for GridGraph<'_, M, P>
+where
+    M: GridCells<Pt<T>>,
+    P: Fn(&M::Value, &M::Value) -> bool,
+    Pt<T>: Add<Output = Pt<T>> + Copy,
+    Direction4: MovementDirection<T>,
+{
+    fn neighbours(&self, node: &(Pt<T>, Direction4)) -> impl Iterator<Item = (Pt<T>, Direction4)> {
+        self.directed_neighbours(node)
+    }
+}
+
+impl<T, M, P, W, C> Graph<(Pt<T>, Direction4), C> for GridGraph<'_, M, P, W>
+where
+    M: GridCells<Pt<T>>,
+    P: Fn(&M::Value, &M::Value) -> bool,
+    W: Fn(&(Pt<T>, Direction4), &(Pt<T>, Direction4)) -> C,
+    C: Cost,
+    Pt<T>: Add<Output = Pt<T>> + Copy,
+    Direction4: MovementDirection<T>,
+{
+    fn neighbours(&self, node: &(Pt<T>, Direction4)) -> impl Iterator<Item = (Pt<T>, Direction4)> {
+        self.directed_neighbours(node)
+    }
+
+    fn dist(&self, a: &(Pt<T>, Direction4), b: &(Pt<T>, Direction4)) -> C {
+        (self.cost)(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::dij;
+    use crate::positioning::pt::matrix_to_map;
+
+    const MAZE: [&str; 4] = ["S.#.", ".##.", "...#", "#..E"];
+
+    fn canvas() -> dynamic::Canvas<char> {
+        dynamic::Canvas::from_lines(MAZE, |c| c)
+    }
+
+    #[test]
+    fn test_pt_nodes() {
+        let canvas = canvas();
+        let graph = GridGraph::new(&canvas, Neighbourhood::Four, |_: &char, to: &char| *to != '#');
+        let output = dij::build(&graph, Pt::new(0, 0));
+        assert_eq!(Some(&6), output.dist.get(&Pt::new(3, 3)));
+        assert_eq!(None, output.dist.get(&Pt::new(3, 0)));
+
+        let graph = GridGraph::new(&canvas, Neighbourhood::Eight, |_: &char, to: &char| *to != '#');
+        let output = dij::build(&graph, Pt::new(0, 0));
+        assert_eq!(Some(&4), output.dist.get(&Pt::new(3, 3)));
+
+        let map: HashMap<Pt, char> = matrix_to_map(MAZE.iter().map(|l| l.chars()));
+        let graph = GridGraph::new(&map, Neighbourhood::Four, |_: &char, to: &char| *to != '#')
+            .with_cost(|_: &Pt, b: &Pt| if map[b] == '.' { 2 } else { 1 });
+        let output = dij::build(&graph, Pt::new(0, 0));
+        assert_eq!(Some(&11), output.dist.get(&Pt::new(3, 3)));
+    }
+
+    #[test]
+    fn test_directed_nodes() {
+        let canvas = canvas();
+        let graph = GridGraph::new(&canvas, Neighbourhood::Four, |_: &char, to: &char| *to != '#');
+        let start = (Pt::new(0, 0), Direction4::Right);
+        let output = dij::build(&graph, start);
+        assert_eq!(Some(&10), output.dist.get(&(Pt::new(3, 3), Direction4::Right)));
+
+        let graph = graph.with_cost(
+            |a: &(Pt, Direction4), b: &(Pt, Direction4)| {
+                if a.0 == b.0 { 1000 } else { 1 }
+            },
+        );
+        let output = dij::build(&graph, start);
+        assert_eq!(Some(&4006), output.dist.get(&(Pt::new(3, 3), Direction4::Right)));
+    }
+}
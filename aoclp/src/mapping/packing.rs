@@ -0,0 +1,518 @@
+use std::fmt::{Display, Formatter};
+use std::iter::repeat_n;
+
+use itertools::Itertools;
+
+use crate::mapping::canvas::fixed::Canvas;
+use crate::positioning::pt::Pt;
+
+/// Solver for polyomino packing problems: given a set of shapes, can a certain number
+/// of copies of each shape be placed in a rectangular region without overlapping?
+///
+/// Shapes can be rotated and flipped. The region does not need to be completely filled.
+///
+/// Obviously impossible packings are rejected by comparing areas and using a checkerboard
+/// parity argument, while packings where each shape fits in its own square are accepted
+/// right away. Other packings are solved via bitboard backtracking.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::canvas::fixed::Canvas;
+/// # use aoclp::mapping::packing::Packer;
+///
+/// let l: Canvas<bool, 3> = Canvas::from_lines(["#..", "#..", "##."], |c| c == '#');
+/// let t = Canvas::from_lines(["###", ".#.", "..."], |c| c == '#');
+/// let packer = Packer::new([l, t]);
+///
+/// assert!(packer.fits(4, 4, &[2, 1]));
+/// assert!(!packer.fits(4, 3, &[2, 1]));
+///
+/// let placement = packer.pack(4, 4, &[2, 1]).unwrap();
+/// assert_eq!(3, placement.pieces.len());
+/// assert_eq!("ABBB\nABC.\nAACC\n..C.", placement.to_string());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Packer {
+    size: usize,
+    shapes: Vec<Shape>,
+}
+
+impl Packer {
+    /// Creates a packer for the given shapes, where `true` marks the cells covered by a shape.
+    ///
+    /// All [variations](Canvas::into_variations) of the shapes will be considered
+    /// when packing them.
+    pub fn new<I, const N: usize>(shapes: I) -> Self
+    where
+        I: IntoIterator<Item = Canvas<bool, N>>,
+    {
+        Self { size: N, shapes: shapes.into_iter().map(Shape::new).collect() }
+    }
+
+    /// Returns `true` if `counts[i]` copies of each shape `i` can be packed in a region
+    /// of `width` by `height` cells.
+    ///
+    /// # Panics
+    ///
+    /// If `counts` does not contain one count per shape, or if solving the packing
+    /// requires backtracking in a region more than 64 cells wide and high.
+    pub fn fits(&self, width: usize, height: usize, counts: &[usize]) -> bool {
+        match self.quick_check(width, height, counts) {
+            Some(fits) => fits,
+            None => self.search(width, height, counts).is_some(),
+        }
+    }
+
+    /// Packs `counts[i]` copies of each shape `i` in a region of `width` by `height` cells,
+    /// returning where each piece ended up, or `None` if the pieces cannot fit.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`fits`](Self::fits).
+    pub fn pack(&self, width: usize, height: usize, counts: &[usize]) -> Option<Placement> {
+        match self.quick_check(width, height, counts) {
+            Some(true) => Some(self.tile(width, height, counts)),
+            Some(false) => None,
+            None => self.search(width, height, counts),
+        }
+    }
+
+    /// Returns `Some(true)` if all pieces fit in their own square, `Some(false)` if pieces
+    /// obviously cannot fit, or `None` if we need to search to find out.
+    fn quick_check(&self, width: usize, height: usize, counts: &[usize]) -> Option<bool> {
+        assert_eq!(self.shapes.len(), counts.len(), "expected one count per shape");
+
+        let area = self.area(counts);
+        let pieces: usize = counts.iter().sum();
+        if area > width * height || !self.parity_fits(width, height, counts, area) {
+            Some(false)
+        } else if pieces <= (width / self.size) * (height / self.size) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the total number of cells covered by the pieces.
+    fn area(&self, counts: &[usize]) -> usize {
+        self.shapes
+            .iter()
+            .zip(counts)
+            .map(|(shape, count)| shape.area * count)
+            .sum()
+    }
+
+    /// Colours the region like a checkerboard: each piece covers a fixed number of cells
+    /// of each colour (although which colour is which depends on where the piece is placed),
+    /// so we need to be able to split pieces so that they do not need more cells of any
+    /// one colour than there are in the region.
+    fn parity_fits(&self, width: usize, height: usize, counts: &[usize], area: usize) -> bool {
+        let (black, white) = ((width * height).div_ceil(2), width * height / 2);
+
+        // reachable[n] is true if we can place pieces so that they cover `n` black cells
+        let mut reachable = vec![false; area + 1];
+        reachable[0] = true;
+        for (shape, count) in self.shapes.iter().zip(counts) {
+            let (min, max) = shape.colours();
+            for _ in 0..*count {
+                for n in (0..=area).rev() {
+                    reachable[n] =
+                        n >= min && (reachable[n - min] || (n >= max && reachable[n - max]));
+                }
+            }
+        }
+
+        (area.saturating_sub(white)..=black.min(area)).any(|n| reachable[n])
+    }
+
+    /// Places each piece in its own square, in reading order.
+    fn tile(&self, width: usize, height: usize, counts: &[usize]) -> Placement {
+        let columns = width / self.size;
+        let pieces = self
+            .shapes
+            .iter()
+            .zip(counts)
+            .enumerate()
+            .flat_map(|(i, (shape, count))| repeat_n((i, shape), *count))
+            .enumerate()
+            .map(|(p, (i, shape))| {
+                let origin = ((p % columns) * self.size, (p / columns) * self.size);
+                shape.orientations[0].place(i, origin)
+            })
+            .collect();
+
+        Placement { width, height, pieces }
+    }
+
+    fn search(&self, width: usize, height: usize, counts: &[usize]) -> Option<Placement> {
+        // Filling the region along its shortest side finds dead ends much sooner
+        if width > height {
+            return self.search(height, width, counts).map(Placement::transpose);
+        }
+        assert!(width <= 64, "region too large to pack: {width}x{height}");
+
+        let area = self.area(counts);
+        let mut search = Search {
+            shapes: &self.shapes,
+            width,
+            height,
+            board: vec![0; height],
+            remaining: counts.to_vec(),
+            placed: Vec::new(),
+        };
+        search
+            .solve(0, counts.iter().sum(), width * height - area)
+            .then(|| Placement {
+                width,
+                height,
+                pieces: search
+                    .placed
+                    .iter()
+                    .map(|&(i, o, origin)| self.shapes[i].orientations[o].place(i, origin))
+                    .collect(),
+            })
+    }
+}
+
+/// Result of a successful [packing](Packer::pack).
+///
+/// Rendering a placement using [`Display`] shows each piece with a different letter,
+/// with empty cells shown as `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Width of the packed region.
+    pub width: usize,
+    /// Height of the packed region.
+    pub height: usize,
+    /// Pieces placed in the region.
+    pub pieces: Vec<Piece>,
+}
+
+/// A piece placed in a region by a [`Packer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piece {
+    /// Index of the shape of this piece.
+    pub shape: usize,
+    /// Cells of the region covered by this piece.
+    pub cells: Vec<Pt>,
+}
+
+impl Placement {
+    fn transpose(self) -> Self {
+        let pieces = self
+            .pieces
+            .into_iter()
+            .map(|piece| Piece {
+                shape: piece.shape,
+                cells: piece
+                    .cells
+                    .into_iter()
+                    .map(|cell| Pt::new(cell.y, cell.x))
+                    .collect(),
+            })
+            .collect();
+        Self { width: self.height, height: self.width, pieces }
+    }
+}
+
+impl Display for Placement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+        let mut grid = vec![vec!['.'; self.width]; self.height];
+        for (p, piece) in self.pieces.iter().enumerate() {
+            for cell in &piece.cells {
+                grid[cell.y as usize][cell.x as usize] = LETTERS[p % LETTERS.len()] as char;
+            }
+        }
+        write!(f, "{}", grid.into_iter().map(String::from_iter).join("\n"))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Shape {
+    area: usize,
+    /// Number of cells of the shape that are on even squares of a checkerboard.
+    even: usize,
+    orientations: Vec<Orientation>,
+}
+
+impl Shape {
+    fn new<const N: usize>(canvas: Canvas<bool, N>) -> Self {
+        let orientations = canvas
+            .into_variations()
+            .map(|variation| Orientation::new(&variation))
+            .unique_by(|orientation| orientation.rows.clone())
+            .collect_vec();
+        let cells = &orientations[0].cells;
+        let even = cells.iter().filter(|(x, y)| (x + y) % 2 == 0).count();
+
+        Self { area: cells.len(), even, orientations }
+    }
+
+    /// Returns the minimum and maximum number of cells of the same colour covered by the shape.
+    fn colours(&self) -> (usize, usize) {
+        let odd = self.area - self.even;
+        (self.even.min(odd), self.even.max(odd))
+    }
+}
+
+/// A shape in one of its orientations, moved to the top-left of its canvas.
+#[derive(Debug, Clone)]
+struct Orientation {
+    width: usize,
+    height: usize,
+    /// X coordinate of the first cell of the top row.
+    anchor: usize,
+    /// Cells covered in each row, as bitmasks.
+    rows: Vec<u64>,
+    cells: Vec<(usize, usize)>,
+}
+
+impl Orientation {
+    fn new<const N: usize>(canvas: &Canvas<bool, N>) -> Self {
+        let cells = canvas
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.positions(|c| *c).map(move |x| (x, y)))
+            .collect_vec();
+        assert!(!cells.is_empty(), "cannot pack an empty shape");
+
+        let min_x = cells.iter().map(|(x, _)| *x).min().unwrap();
+        let min_y = cells.iter().map(|(_, y)| *y).min().unwrap();
+        let cells = cells
+            .into_iter()
+            .map(|(x, y)| (x - min_x, y - min_y))
+            .collect_vec();
+        let width = cells.iter().map(|(x, _)| x + 1).max().unwrap();
+        let height = cells.iter().map(|(_, y)| y + 1).max().unwrap();
+        let mut rows = vec![0u64; height];
+        for (x, y) in &cells {
+            rows[*y] |= 1 << x;
+        }
+
+        Self { width, height, anchor: rows[0].trailing_zeros() as usize, rows, cells }
+    }
+
+    fn place(&self, shape: usize, (ox, oy): (usize, usize)) -> Piece {
+        let cells = self
+            .cells
+            .iter()
+            .map(|(x, y)| Pt::new((ox + x) as i64, (oy + y) as i64))
+            .collect();
+        Piece { shape, cells }
+    }
+}
+
+struct Search<'a> {
+    shapes: &'a [Shape],
+    width: usize,
+    height: usize,
+    /// Cells of the region that are covered or left empty, as one bitmask per row.
+    board: Vec<u64>,
+    remaining: Vec<usize>,
+    /// Pieces placed so far, as `(shape, orientation, origin)`.
+    placed: Vec<(usize, usize, (usize, usize))>,
+}
+
+impl Search<'_> {
+    /// Decides what to do with the first undecided cell at or after `cursor`: either cover it
+    /// with a piece, or leave it empty if we can spare it. `slack` is the number of cells that
+    /// can still be left empty.
+    fn solve(&mut self, cursor: usize, pieces: usize, slack: usize) -> bool {
+        if pieces == 0 {
+            return true;
+        }
+        let Some(cell) = (cursor..self.width * self.height)
+            .find(|c| self.board[c / self.width] & (1 << (c % self.width)) == 0)
+        else {
+            return false;
+        };
+        let (x, y) = (cell % self.width, cell / self.width);
+
+        let shapes = self.shapes;
+        for (i, shape) in shapes.iter().enumerate() {
+            if self.remaining[i] == 0 {
+                continue;
+            }
+            for (o, orientation) in shape.orientations.iter().enumerate() {
+                let Some(ox) = x.checked_sub(orientation.anchor) else {
+                    continue;
+                };
+                if ox + orientation.width > self.width
+                    || y + orientation.height > self.height
+                    || !self.toggle_if(orientation, (ox, y), |row, mask| row & mask == 0)
+                {
+                    continue;
+                }
+
+                self.remaining[i] -= 1;
+                self.placed.push((i, o, (ox, y)));
+                if self.solve(cell + 1, pieces - 1, slack) {
+                    return true;
+                }
+                self.placed.pop();
+                self.remaining[i] += 1;
+                self.toggle_if(orientation, (ox, y), |_, _| true);
+            }
+        }
+
+        if slack > 0 {
+            self.board[y] |= 1 << x;
+            if self.solve(cell + 1, pieces, slack - 1) {
+                return true;
+            }
+            self.board[y] &= !(1 << x);
+        }
+
+        false
+    }
+
+    /// Toggles the cells covered by `orientation` placed at `(ox, oy)`, if `pred` is true
+    /// for all rows of the board given the row's current state and the orientation's mask.
+    fn toggle_if<F>(&mut self, orientation: &Orientation, (ox, oy): (usize, usize), pred: F) -> bool
+    where
+        F: Fn(u64, u64) -> bool,
+    {
+        let rows = &mut self.board[oy..oy + orientation.height];
+        let masks = orientation.rows.iter().map(|mask| mask << ox);
+        if !rows
+            .iter()
+            .zip(masks.clone())
+            .all(|(row, mask)| pred(*row, mask))
+        {
+            return false;
+        }
+        rows.iter_mut()
+            .zip(masks)
+            .for_each(|(row, mask)| *row ^= mask);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_packer() -> Packer {
+        let shapes = [
+            ["###", "##.", "##."],
+            ["###", "##.", ".##"],
+            [".##", "###", "##."],
+            ["##.", "###", "##."],
+            ["###", "#..", "###"],
+            ["###", ".#.", "###"],
+        ];
+        Packer::new(
+            shapes
+                .into_iter()
+                .map(|lines| Canvas::<bool, 3>::from_lines(lines, |c| c == '#')),
+        )
+    }
+
+    /// Checks that `placement` is a valid packing of `counts` pieces in a `width` by `height`
+    /// region: every cell is in bounds, no two pieces overlap and each piece covers as many
+    /// cells as its shape.
+    fn assert_valid(
+        packer: &Packer,
+        placement: &Placement,
+        width: usize,
+        height: usize,
+        counts: &[usize],
+    ) {
+        assert_eq!((width, height), (placement.width, placement.height));
+
+        let cells = placement
+            .pieces
+            .iter()
+            .flat_map(|piece| &piece.cells)
+            .collect_vec();
+        assert!(cells.iter().all(
+            |cell| (0..width as i64).contains(&cell.x) && (0..height as i64).contains(&cell.y)
+        ));
+        assert!(cells.iter().all_unique(), "pieces overlap");
+
+        for piece in &placement.pieces {
+            assert_eq!(packer.shapes[piece.shape].area, piece.cells.len());
+        }
+        let placed = placement.pieces.iter().counts_by(|piece| piece.shape);
+        let placed = (0..counts.len()).map(|i| placed.get(&i).copied().unwrap_or_default());
+        assert_eq!(counts, placed.collect_vec());
+    }
+
+    #[test]
+    fn test_orientations() {
+        let packer = example_packer();
+        let counts = packer
+            .shapes
+            .iter()
+            .map(|shape| shape.orientations.len())
+            .collect_vec();
+        assert_eq!(vec![8, 8, 2, 4, 4, 2], counts);
+    }
+
+    #[test]
+    fn test_pack() {
+        let packer = example_packer();
+
+        let placement = packer.pack(4, 4, &[0, 0, 0, 0, 2, 0]).unwrap();
+        assert_valid(&packer, &placement, 4, 4, &[0, 0, 0, 0, 2, 0]);
+        assert_eq!(2, placement.pieces.len());
+        assert!(placement.to_string().lines().all(|line| line.len() == 4));
+        assert_eq!(
+            14,
+            placement
+                .to_string()
+                .chars()
+                .filter(char::is_ascii_uppercase)
+                .count()
+        );
+
+        // Wider than tall, so the search runs on the transposed region
+        let placement = packer.pack(12, 5, &[1, 0, 1, 0, 2, 2]).unwrap();
+        assert_valid(&packer, &placement, 12, 5, &[1, 0, 1, 0, 2, 2]);
+        let rendered = placement.to_string();
+        assert_eq!(5, rendered.lines().count());
+        assert!(rendered.lines().all(|line| line.len() == 12));
+        assert_eq!(42, rendered.chars().filter(char::is_ascii_uppercase).count());
+        assert!(!packer.fits(12, 5, &[1, 0, 1, 0, 3, 2]));
+
+        let placement = packer.pack(5, 12, &[1, 0, 1, 0, 2, 2]).unwrap();
+        assert_valid(&packer, &placement, 5, 12, &[1, 0, 1, 0, 2, 2]);
+    }
+
+    #[test]
+    fn test_quick_checks() {
+        let packer = example_packer();
+
+        // Too big
+        assert_eq!(None, packer.pack(3, 4, &[0, 0, 0, 0, 2, 0]));
+
+        // Each piece fits in its own square
+        let placement = packer.pack(9, 3, &[1, 1, 0, 0, 0, 1]).unwrap();
+        assert_valid(&packer, &placement, 9, 3, &[1, 1, 0, 0, 0, 1]);
+        let expected = "AAABBBCCC\nAA.BB..C.\nAA..BBCCC";
+        assert_eq!(expected, placement.to_string());
+    }
+
+    #[test]
+    fn test_parity() {
+        // Dominoes always cover one black and one white cell, but T-tetrominoes cover
+        // three cells of one colour and one of the other
+        let domino: Canvas<bool, 3> = Canvas::from_lines(["##.", "...", "..."], |c| c == '#');
+        let t = Canvas::from_lines(["###", ".#.", "..."], |c| c == '#');
+        let packer = Packer::new([domino, t]);
+
+        assert!(packer.fits(4, 2, &[4, 0]));
+        assert!(!packer.parity_fits(4, 4, &[6, 1], 16));
+        assert!(!packer.fits(4, 4, &[6, 1]));
+        assert!(packer.parity_fits(4, 4, &[4, 2], 16));
+        assert!(packer.fits(4, 4, &[4, 2]));
+
+        let placement = packer.pack(4, 2, &[4, 0]).unwrap();
+        assert_valid(&packer, &placement, 4, 2, &[4, 0]);
+        let placement = packer.pack(4, 4, &[4, 2]).unwrap();
+        assert_valid(&packer, &placement, 4, 4, &[4, 2]);
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Index;
+
+use gratte::IntoEnumIterator;
+
+use crate::mapping::grid::{GridCells, GridPoints, Neighbourhood};
+use crate::positioning::direction::four_points::Direction4;
+use crate::positioning::direction::{Direction, MovementDirection};
+use crate::positioning::pt::Pt;
+
+/// A connected region of cells in a 2D grid, as returned by [`flood_fill`] or [`Regions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    cells: HashSet<Pt>,
+}
+
+impl Region {
+    /// Returns `true` if the cell at `pt` is part of the region.
+    pub fn contains(&self, pt: Pt) -> bool {
+        self.cells.contains(&pt)
+    }
+
+    /// Returns an [iterator](Iterator) of the region's cells, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = Pt> {
+        self.cells.iter().copied()
+    }
+
+    /// Returns the number of cells in the region.
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the length of the region's perimeter, i.e. the number of cell edges
+    /// separating a cell of the region from a cell outside the region.
+    ///
+    /// This includes the perimeter of any hole inside the region.
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .flat_map(|pt| pt.four_neighbours())
+            .filter(|n| !self.contains(*n))
+            .count()
+    }
+
+    /// Returns the number of straight sides of the region's perimeter (including the
+    /// perimeter of any hole inside the region).
+    ///
+    /// This is computed by counting corners, which is equivalent for closed shapes.
+    pub fn sides(&self) -> usize {
+        self.cells
+            .iter()
+            .flat_map(|&pt| {
+                Direction4::iter().filter(move |&dir| {
+                    let side = dir.turn_right();
+                    let a = self.contains(pt + dir.displacement());
+                    let b = self.contains(pt + side.displacement());
+                    let diagonal = self.contains(pt + dir.displacement() + side.displacement());
+                    (!a && !b) || (a && b && !diagonal)
+                })
+            })
+            .count()
+    }
+
+    /// Returns the top-left and bottom-right corners (inclusive) of the smallest rectangle
+    /// containing all cells of the region, or `None` if the region is empty.
+    pub fn bounds(&self) -> Option<(Pt, Pt)> {
+        self.cells.iter().fold(None, |bounds, &pt| {
+            Some(match bounds {
+                None => (pt, pt),
+                Some((min, max)) => (
+                    Pt::new(min.x.min(pt.x), min.y.min(pt.y)),
+                    Pt::new(max.x.max(pt.x), max.y.max(pt.y)),
+                ),
+            })
+        })
+    }
+
+    /// Returns an [iterator](Iterator) of the region's cells that have at least one
+    /// orthogonal neighbour outside the region, in no particular order.
+    pub fn boundary(&self) -> impl Iterator<Item = Pt> {
+        self.cells()
+            .filter(|pt| pt.four_neighbours().any(|n| !self.contains(n)))
+    }
+}
+
+/// Finds the region of `grid` containing the cell at `seed`.
+///
+/// The region is expanded to neighbouring cells (as determined by the [`Neighbourhood`])
+/// as long as they are in the grid and the `connected` predicate returns `true` for the
+/// `(from, to)` cells. If `seed` is outside the grid, the returned region is empty.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::canvas::dynamic::Canvas;
+/// # use aoclp::mapping::grid::Neighbourhood;
+/// # use aoclp::mapping::regions::flood_fill;
+/// # use aoclp::positioning::pt::Pt;
+///
+/// let canvas = Canvas::from_lines(["##.", "..#", "##."], |c| c == '#');
+///
+/// let region = flood_fill(&canvas, Pt::new(0, 0), Neighbourhood::Four, |_, to| *to);
+/// assert_eq!(2, region.area());
+/// assert_eq!(6, region.perimeter());
+///
+/// let region = flood_fill(&canvas, Pt::new(0, 0), Neighbourhood::Eight, |_, to| *to);
+/// assert_eq!(5, region.area());
+/// assert_eq!(Some((Pt::new(0, 0), Pt::new(2, 2))), region.bounds());
+/// ```
+pub fn flood_fill<G, F>(
+    grid: &G,
+    seed: Pt,
+    neighbourhood: Neighbourhood,
+    mut connected: F,
+) -> Region
+where
+    G: GridCells<Pt>,
+    F: FnMut(&G::Value, &G::Value) -> bool,
+{
+    let mut cells = HashSet::new();
+    if grid.cell(&seed).is_some() {
+        cells.insert(seed);
+    }
+
+    let mut stack = cells.iter().copied().collect::<Vec<_>>();
+    while let Some(pt) = stack.pop() {
+        let value = grid.cell(&pt).unwrap();
+        for n in neighbourhood.of(pt) {
+            if !cells.contains(&n)
+                && let Some(n_value) = grid.cell(&n)
+                && connected(value, n_value)
+            {
+                cells.insert(n);
+                stack.push(n);
+            }
+        }
+    }
+
+    Region { cells }
+}
+
+/// All connected [regions](Region) of a 2D grid, each identified by a label.
+///
+/// Labels are assigned in the order in which the grid's [points](GridPoints::points) are
+/// returned, so for canvases, region `0` contains the top-left cell.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::canvas::dynamic::Canvas;
+/// # use aoclp::mapping::grid::Neighbourhood;
+/// # use aoclp::mapping::regions::Regions;
+/// # use aoclp::positioning::pt::Pt;
+///
+/// let canvas = Canvas::from_lines(["AAAA", "BBCD", "BBCC", "EEEC"], |c| c);
+/// let regions = Regions::by_equality(&canvas, Neighbourhood::Four);
+/// assert_eq!(5, regions.len());
+///
+/// let c = regions.region_of(Pt::new(2, 1)).unwrap();
+/// assert_eq!((4, 10, 8), (c.area(), c.perimeter(), c.sides()));
+///
+/// let price: usize = regions.iter().map(|r| r.area() * r.perimeter()).sum();
+/// assert_eq!(140, price);
+///
+/// let regions = Regions::matching(&canvas, Neighbourhood::Eight, |c| *c == 'C' || *c == 'D');
+/// assert_eq!(1, regions.len());
+/// assert_eq!(Some(0), regions.label_of(Pt::new(3, 1)));
+/// assert_eq!(None, regions.label_of(Pt::new(0, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    regions: Vec<Region>,
+    labels: HashMap<Pt, usize>,
+}
+
+impl Regions {
+    /// Labels all regions of `grid` where neighbouring cells (as determined by the
+    /// [`Neighbourhood`]) are connected if their values are equal.
+    pub fn by_equality<G>(grid: &G, neighbourhood: Neighbourhood) -> Self
+    where
+        G: GridPoints<Pt>,
+        G::Value: PartialEq,
+    {
+        Self::new(grid, neighbourhood, |_| true, |a, b| a == b)
+    }
+
+    /// Labels all regions of `grid` made of cells whose values match the given `predicate`.
+    /// Neighbouring cells (as determined by the [`Neighbourhood`]) are connected if they
+    /// both match; cells that do not match are not part of any region.
+    pub fn matching<G, P>(grid: &G, neighbourhood: Neighbourhood, predicate: P) -> Self
+    where
+        G: GridPoints<Pt>,
+        P: Fn(&G::Value) -> bool,
+    {
+        Self::new(grid, neighbourhood, &predicate, |_, b| predicate(b))
+    }
+
+    /// Returns the number of regions.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns `true` if there are no regions.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Returns an [iterator](Iterator) of all regions, in label order.
+    pub fn iter(&self) -> impl Iterator<Item = &Region> {
+        self.regions.iter()
+    }
+
+    /// Returns the label of the region containing the cell at `pt`, or `None` if the cell
+    /// is not part of any region.
+    pub fn label_of(&self, pt: Pt) -> Option<usize> {
+        self.labels.get(&pt).copied()
+    }
+
+    /// Returns the region containing the cell at `pt`, or `None` if the cell is not part
+    /// of any region.
+    pub fn region_of(&self, pt: Pt) -> Option<&Region> {
+        self.label_of(pt).map(|label| &self.regions[label])
+    }
+
+    fn new<G, I, C>(grid: &G, neighbourhood: Neighbourhood, include: I, connected: C) -> Self
+    where
+        G: GridPoints<Pt>,
+        I: Fn(&G::Value) -> bool,
+        C: Fn(&G::Value, &G::Value) -> bool,
+    {
+        let mut regions = Vec::new();
+        let mut labels = HashMap::new();
+
+        for pt in grid.points() {
+            if labels.contains_key(&pt) || !grid.cell(&pt).is_some_and(&include) {
+                continue;
+            }
+
+            let region = flood_fill(grid, pt, neighbourhood, &connected);
+            labels.extend(region.cells().map(|pt| (pt, regions.len())));
+            regions.push(region);
+        }
+
+        Self { regions, labels }
+    }
+}
+
+impl Index<usize> for Regions {
+    type Output = Region;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.regions[index]
+    }
+}
+
+impl IntoIterator for Regions {
+    type Item = Region;
+    type IntoIter = std::vec::IntoIter<Region>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.regions.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::canvas::dynamic::Canvas;
+    use crate::mapping::sparse::SparseGrid;
+
+    fn garden_price<const N: usize>(lines: [&str; N]) -> (usize, usize) {
+        let canvas = Canvas::from_lines(lines, |c| c);
+        Regions::by_equality(&canvas, Neighbourhood::Four)
+            .iter()
+            .fold((0, 0), |(by_perimeter, by_sides), r| {
+                (by_perimeter + r.area() * r.perimeter(), by_sides + r.area() * r.sides())
+            })
+    }
+
+    #[test]
+    fn test_garden() {
+        assert_eq!((772, 436), garden_price(["OOOOO", "OXOXO", "OOOOO", "OXOXO", "OOOOO"]));
+        assert_eq!((692, 236), garden_price(["EEEEE", "EXXXX", "EEEEE", "EXXXX", "EEEEE"]));
+        assert_eq!(
+            (1184, 368),
+            garden_price(["AAAAAA", "AAABBA", "AAABBA", "ABBAAA", "ABBAAA", "AAAAAA"])
+        );
+    }
+
+    #[test]
+    fn test_boundary() {
+        let canvas = Canvas::of(4, 3, 0);
+        let region = flood_fill(&canvas, Pt::new(1, 1), Neighbourhood::Four, |a, b| a == b);
+        assert_eq!(12, region.area());
+        assert_eq!(4, region.sides());
+
+        let boundary = region.boundary().collect::<Vec<_>>();
+        assert_eq!(10, boundary.len());
+        assert!(!boundary.contains(&Pt::new(1, 1)) && !boundary.contains(&Pt::new(2, 1)));
+
+        assert_eq!(Some((Pt::new(0, 0), Pt::new(3, 2))), region.bounds());
+
+        let outside = flood_fill(&canvas, Pt::new(4, 0), Neighbourhood::Four, |_, _| true);
+        assert_eq!(0, outside.area());
+        assert_eq!(None, outside.bounds());
+    }
+
+    #[test]
+    fn test_point_maps() {
+        let grid: SparseGrid<bool> =
+            [(Pt::new(-2, -2), true), (Pt::new(-1, -1), true), (Pt::new(3, 0), true)]
+                .into_iter()
+                .collect();
+        let regions = Regions::matching(&grid, Neighbourhood::Four, |b| *b);
+        assert_eq!(3, regions.len());
+        let regions = Regions::matching(&grid, Neighbourhood::Eight, |b| *b);
+        assert_eq!(2, regions.len());
+        assert_eq!(Some((Pt::new(-2, -2), Pt::new(-1, -1))), regions[0].bounds());
+
+        let map: HashMap<Pt, bool> = grid.into();
+        let regions = Regions::by_equality(&map, Neighbourhood::Eight);
+        assert_eq!(2, regions.len());
+        assert_eq!(regions.label_of(Pt::new(-2, -2)), regions.label_of(Pt::new(-1, -1)));
+    }
+}
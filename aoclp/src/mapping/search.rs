@@ -0,0 +1,560 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::{mem, vec};
+
+use num::{NumCast, Signed, cast};
+
+use crate::mapping::dij::{Cost, Graph, Visit, assemble_path};
+use crate::positioning::pt::{Pt, manhattan};
+
+/// Result of a successful search: the goal that was found, the cost to reach it and
+/// a map of predecessors that can be passed to [`assemble_path`].
+#[derive(Debug, Clone)]
+pub struct Found<T, C = usize> {
+    pub start: T,
+    pub goal: T,
+    pub cost: C,
+    pub prev: HashMap<T, T>,
+}
+
+impl<T, C> Found<T, C>
+where
+    T: Clone + Eq + Hash,
+{
+    /// Returns the path from `start` to `goal`, both included.
+    pub fn path(&self) -> impl Iterator<Item = T> {
+        assemble_path(&self.prev, &self.start, &self.goal)
+    }
+}
+
+/// Heuristic used by [`astar`] to estimate the cost of reaching a goal from a node.
+///
+/// To find the shortest path, the heuristic must never overestimate the actual cost.
+pub trait Heuristic<T, C> {
+    fn estimate(&self, node: &T) -> C;
+}
+
+impl<T, C, F> Heuristic<T, C> for F
+where
+    F: Fn(&T) -> C,
+{
+    fn estimate(&self, node: &T) -> C {
+        self(node)
+    }
+}
+
+/// [Heuristic] estimating the cost of reaching a goal [point](Pt) via its
+/// [Manhattan distance](manhattan).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Manhattan<T = i64>(pub Pt<T>);
+
+impl<T, C> Heuristic<Pt<T>, C> for Manhattan<T>
+where
+    T: Signed + NumCast + Copy,
+    C: NumCast,
+{
+    fn estimate(&self, node: &Pt<T>) -> C {
+        cast(manhattan(*node, self.0)).unwrap()
+    }
+}
+
+/// Finds the shortest path from `start` to the first node for which `is_goal` returns `true`
+/// using the [A* search algorithm](https://en.wikipedia.org/wiki/A*_search_algorithm).
+pub fn astar<T, G, C, F, H>(
+    graph: &G,
+    start: T,
+    mut is_goal: F,
+    heuristic: &H,
+) -> Option<Found<T, C>>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+    F: FnMut(&T) -> bool,
+    H: Heuristic<T, C>,
+{
+    let mut dist = HashMap::new();
+    let mut prev = HashMap::new();
+    dist.insert(start.clone(), C::zero());
+
+    let mut q = BinaryHeap::new();
+    q.push(Visit { cost: heuristic.estimate(&start), node: (C::zero(), start.clone()) });
+
+    while let Some(Visit { node: (u_dist, u), .. }) = q.pop() {
+        if dist.get(&u).is_some_and(|d| u_dist > *d) {
+            continue;
+        }
+        if is_goal(&u) {
+            return Some(Found { start, goal: u, cost: u_dist, prev });
+        }
+
+        for v in graph.neighbours(&u) {
+            let alt = u_dist + graph.dist(&u, &v);
+            let improved = match dist.entry(v.clone()) {
+                Entry::Vacant(e) => {
+                    e.insert(alt);
+                    true
+                },
+                Entry::Occupied(mut e) if alt < *e.get() => {
+                    e.insert(alt);
+                    true
+                },
+                _ => false,
+            };
+            if improved {
+                prev.insert(v.clone(), u.clone());
+                q.push(Visit { cost: alt + heuristic.estimate(&v), node: (alt, v) });
+            }
+        }
+    }
+
+    None
+}
+
+/// Breadth-first traversal of a [`Graph`], returned by [`bfs`].
+///
+/// Iterating returns the nodes layer by layer: the first layer contains only the starting node,
+/// the second layer contains its neighbours, and so on. Each node is returned only once.
+/// Edge costs are ignored.
+pub struct Bfs<'g, T, G, C = usize> {
+    graph: &'g G,
+    layer: Vec<T>,
+    seen: HashSet<T>,
+    prev: HashMap<T, T>,
+    cost: PhantomData<C>,
+}
+
+impl<T, G, C> Bfs<'_, T, G, C> {
+    /// Returns the predecessors of all nodes discovered so far, which can be passed to
+    /// [`assemble_path`].
+    pub fn prev(&self) -> &HashMap<T, T> {
+        &self.prev
+    }
+
+    /// Consumes the traversal and returns the predecessors of all nodes discovered so far.
+    pub fn into_prev(self) -> HashMap<T, T> {
+        self.prev
+    }
+}
+
+impl<T, G, C> Iterator for Bfs<'_, T, G, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.layer.is_empty() {
+            return None;
+        }
+
+        let mut next_layer = Vec::new();
+        for u in &self.layer {
+            for v in self.graph.neighbours(u) {
+                if self.seen.insert(v.clone()) {
+                    self.prev.insert(v.clone(), u.clone());
+                    next_layer.push(v);
+                }
+            }
+        }
+
+        Some(mem::replace(&mut self.layer, next_layer))
+    }
+}
+
+/// Starts a [breadth-first traversal](Bfs) of `graph` from `start`.
+pub fn bfs<T, G, C>(graph: &G, start: T) -> Bfs<'_, T, G, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    Bfs {
+        graph,
+        layer: vec![start.clone()],
+        seen: HashSet::from([start]),
+        prev: HashMap::new(),
+        cost: PhantomData,
+    }
+}
+
+/// Finds the path with the fewest steps from `start` to the first node for which
+/// `is_goal` returns `true` using a [breadth-first traversal](Bfs).
+pub fn bfs_to<T, G, C, F>(graph: &G, start: T, mut is_goal: F) -> Option<Found<T>>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+    F: FnMut(&T) -> bool,
+{
+    let mut traversal = bfs(graph, start.clone());
+    let (cost, goal) = traversal
+        .by_ref()
+        .enumerate()
+        .find_map(|(depth, layer)| layer.into_iter().find(&mut is_goal).map(|n| (depth, n)))?;
+
+    Some(Found { start, goal, cost, prev: traversal.into_prev() })
+}
+
+/// Policy determining whether a [depth-first traversal](Dfs) can visit a node more than once.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum VisitPolicy {
+    /// Each node is visited at most once during the whole traversal.
+    Once,
+    /// A node can be visited again, but not if it is already on the current path.
+    /// This enumerates all simple paths from the starting node, which can be very expensive.
+    NotOnPath,
+    /// Nodes can be visited any number of times. Only use on graphs without cycles.
+    Always,
+}
+
+/// Iterative depth-first traversal of a [`Graph`], returned by [`dfs`].
+///
+/// Iterating returns nodes in pre-order, starting with the starting node. In-between
+/// calls to [`next`](Iterator::next), [`path`](Self::path) returns the path that led
+/// to the last returned node.
+pub struct Dfs<'g, T, G, C = usize> {
+    graph: &'g G,
+    policy: VisitPolicy,
+    pending: Option<T>,
+    stack: Vec<(T, vec::IntoIter<T>)>,
+    visited: HashSet<T>,
+    prev: HashMap<T, T>,
+    cost: PhantomData<C>,
+}
+
+impl<T, G, C> Dfs<'_, T, G, C> {
+    /// Returns the path from the starting node to the last node returned by the traversal.
+    pub fn path(&self) -> impl Iterator<Item = &T> {
+        self.stack.iter().map(|(n, _)| n)
+    }
+
+    /// Returns the depth of the last node returned by the traversal (the starting node
+    /// is at depth 0).
+    pub fn depth(&self) -> usize {
+        self.stack.len().saturating_sub(1)
+    }
+
+    /// Returns the predecessors of the nodes visited so far, which can be passed to
+    /// [`assemble_path`]. Only meaningful with [`VisitPolicy::Once`]; with other policies,
+    /// each node's predecessor is the one through which it was last visited.
+    pub fn prev(&self) -> &HashMap<T, T> {
+        &self.prev
+    }
+}
+
+impl<T, G, C> Iterator for Dfs<'_, T, G, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(node) = self.pending.take() {
+            return Some(self.enter(node));
+        }
+
+        loop {
+            let (_, neighbours) = self.stack.last_mut()?;
+            match neighbours.next() {
+                Some(v) if self.can_visit(&v) => {
+                    let (u, _) = self.stack.last().unwrap();
+                    self.prev.insert(v.clone(), u.clone());
+                    return Some(self.enter(v));
+                },
+                Some(_) => (),
+                None => {
+                    let (u, _) = self.stack.pop().unwrap();
+                    if self.policy == VisitPolicy::NotOnPath {
+                        self.visited.remove(&u);
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl<T, G, C> Dfs<'_, T, G, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    fn can_visit(&self, node: &T) -> bool {
+        self.policy == VisitPolicy::Always || !self.visited.contains(node)
+    }
+
+    fn enter(&mut self, node: T) -> T {
+        if self.policy != VisitPolicy::Always {
+            self.visited.insert(node.clone());
+        }
+        let neighbours: Vec<_> = self.graph.neighbours(&node).collect();
+        self.stack.push((node.clone(), neighbours.into_iter()));
+        node
+    }
+}
+
+/// Starts a [depth-first traversal](Dfs) of `graph` from `start`, using the given
+/// [visit policy](VisitPolicy).
+pub fn dfs<T, G, C>(graph: &G, start: T, policy: VisitPolicy) -> Dfs<'_, T, G, C>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    Dfs {
+        graph,
+        policy,
+        pending: Some(start),
+        stack: Vec::new(),
+        visited: HashSet::new(),
+        prev: HashMap::new(),
+        cost: PhantomData,
+    }
+}
+
+/// Finds the path with the fewest steps between `start` and `goal` by running breadth-first
+/// searches from both ends at once.
+///
+/// This assumes that edges of `graph` can be traversed in both directions. For directed graphs,
+/// use [`bidirectional_bfs_with`].
+pub fn bidirectional_bfs<T, G, C>(graph: &G, start: T, goal: T) -> Option<Found<T>>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    bidirectional_bfs_with(graph, graph, start, goal)
+}
+
+/// Finds the path with the fewest steps between `start` and `goal` by running a breadth-first
+/// search from `start` in `forward`, and another from `goal` in `backward`, where `backward`
+/// is the same graph as `forward` but with all edges reversed.
+pub fn bidirectional_bfs_with<T, F, B, C>(
+    forward: &F,
+    backward: &B,
+    start: T,
+    goal: T,
+) -> Option<Found<T>>
+where
+    T: Clone + Eq + Hash,
+    F: Graph<T, C>,
+    B: Graph<T, C>,
+    C: Cost,
+{
+    if start == goal {
+        return Some(Found { start, goal, cost: 0, prev: HashMap::new() });
+    }
+
+    let mut f_links = HashMap::from([(start.clone(), (start.clone(), 0))]);
+    let mut b_links = HashMap::from([(goal.clone(), (goal.clone(), 0))]);
+    let mut f_queue = VecDeque::from([start.clone()]);
+    let mut b_queue = VecDeque::from([goal.clone()]);
+
+    let meet = loop {
+        if f_queue.is_empty() || b_queue.is_empty() {
+            return None;
+        }
+
+        let meet = match f_queue.len() <= b_queue.len() {
+            true => expand_layer(forward, &mut f_queue, &mut f_links, &b_links),
+            false => expand_layer(backward, &mut b_queue, &mut b_links, &f_links),
+        };
+        if let Some(meet) = meet {
+            break meet;
+        }
+    };
+
+    let cost = f_links[&meet].1 + b_links[&meet].1;
+    let mut prev: HashMap<_, _> = f_links
+        .into_iter()
+        .filter(|(n, _)| *n != start)
+        .map(|(n, (p, _))| (n, p))
+        .collect();
+    let mut n = meet;
+    while n != goal {
+        let (next, _) = b_links.remove(&n).unwrap();
+        prev.insert(next.clone(), n);
+        n = next;
+    }
+
+    Some(Found { start, goal, cost, prev })
+}
+
+/// Expands one layer of a breadth-first search used by [`bidirectional_bfs_with`].
+/// `links` maps each discovered node to its parent and depth.
+///
+/// If nodes discovered by the other search are found, returns the one that is closest
+/// to the other search's origin.
+fn expand_layer<T, G, C>(
+    graph: &G,
+    queue: &mut VecDeque<T>,
+    links: &mut HashMap<T, (T, usize)>,
+    other_links: &HashMap<T, (T, usize)>,
+) -> Option<T>
+where
+    T: Clone + Eq + Hash,
+    G: Graph<T, C>,
+    C: Cost,
+{
+    let mut meet: Option<(usize, T)> = None;
+    for _ in 0..queue.len() {
+        let u = queue.pop_front().unwrap();
+        let depth = links[&u].1 + 1;
+        for v in graph.neighbours(&u) {
+            if let Entry::Vacant(e) = links.entry(v.clone()) {
+                e.insert((u.clone(), depth));
+                if let Some((_, other_depth)) = other_links.get(&v)
+                    && meet.as_ref().is_none_or(|(d, _)| other_depth < d)
+                {
+                    meet = Some((*other_depth, v.clone()));
+                }
+                queue.push_back(v);
+            }
+        }
+    }
+    meet.map(|(_, v)| v)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    const MAZE: &[&str] = &["S....#..", ".##.....", ".#..##.#", ".#.#...#", "...#.#.E", "###.####"];
+
+    struct Maze(HashSet<Pt>);
+
+    impl Maze {
+        fn new() -> Self {
+            Self(
+                MAZE.iter()
+                    .enumerate()
+                    .flat_map(|(y, row)| {
+                        row.chars()
+                            .enumerate()
+                            .filter(|(_, c)| *c != '#')
+                            .map(move |(x, _)| Pt::new(x as i64, y as i64))
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    impl Graph<Pt> for Maze {
+        fn neighbours(&self, node: &Pt) -> impl Iterator<Item = Pt> {
+            node.four_neighbours().filter(|n| self.0.contains(n))
+        }
+    }
+
+    const START: Pt = Pt::new(0, 0);
+    const GOAL: Pt = Pt::new(7, 4);
+
+    fn assert_valid_path(path: &[Pt], len: usize) {
+        assert_eq!(len, path.len());
+        assert_eq!(Some(&START), path.first());
+        assert_eq!(Some(&GOAL), path.last());
+        assert!(
+            path.iter()
+                .tuple_windows()
+                .all(|(a, b)| manhattan(*a, *b) == 1)
+        );
+    }
+
+    #[test]
+    fn test_astar() {
+        let maze = Maze::new();
+        let found = astar(&maze, START, |n| *n == GOAL, &Manhattan(GOAL)).unwrap();
+        assert_eq!(11, found.cost);
+        assert_valid_path(&found.path().collect_vec(), 12);
+
+        assert!(astar(&maze, START, |n| *n == Pt::new(3, 5), &Manhattan(GOAL)).is_none());
+        assert_eq!(
+            found.cost,
+            astar(&maze, START, |n| *n == GOAL, &|_: &Pt| 0)
+                .unwrap()
+                .cost
+        );
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = Maze::new();
+        let layers = bfs(&maze, START).collect_vec();
+        assert_eq!(vec![START], layers[0]);
+        assert_eq!(
+            vec![Pt::new(0, 1), Pt::new(1, 0)],
+            layers[1].iter().copied().sorted().collect_vec()
+        );
+        assert_eq!(maze.0.len() - 1, layers.iter().map(Vec::len).sum::<usize>());
+        assert!(layers[11].contains(&GOAL));
+
+        let found = bfs_to(&maze, START, |n| *n == GOAL).unwrap();
+        assert_eq!(11, found.cost);
+        assert_valid_path(&found.path().collect_vec(), 12);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let maze = Maze::new();
+        let mut traversal = dfs(&maze, START, VisitPolicy::Once);
+        assert_eq!(Some(START), traversal.next());
+        assert_eq!(0, traversal.depth());
+        assert!(traversal.by_ref().contains(&GOAL));
+        assert_valid_path(&traversal.path().copied().collect_vec(), traversal.depth() + 1);
+        assert_eq!(maze.0.len() - 1, dfs(&maze, START, VisitPolicy::Once).count());
+
+        let mut traversal = dfs(&maze, START, VisitPolicy::NotOnPath);
+        let mut path_lens = Vec::new();
+        while let Some(n) = traversal.next() {
+            if n == GOAL {
+                assert_valid_path(&traversal.path().copied().collect_vec(), traversal.depth() + 1);
+                path_lens.push(traversal.depth());
+            }
+        }
+        assert_eq!(vec![11, 11, 17, 19], path_lens.into_iter().sorted().collect_vec());
+    }
+
+    #[test]
+    fn test_bidirectional_bfs() {
+        let maze = Maze::new();
+        let found = bidirectional_bfs(&maze, START, GOAL).unwrap();
+        assert_eq!(11, found.cost);
+        assert_valid_path(&found.path().collect_vec(), 12);
+
+        assert!(bidirectional_bfs(&maze, START, Pt::new(3, 5)).is_none());
+        assert_eq!(0, bidirectional_bfs(&maze, START, START).unwrap().cost);
+    }
+
+    /// [`Maze`] where every step has a toll; traversals count steps and ignore the toll.
+    struct TollMaze(Maze);
+
+    impl Graph<Pt, i64> for TollMaze {
+        fn neighbours(&self, node: &Pt) -> impl Iterator<Item = Pt> {
+            self.0.neighbours(node)
+        }
+
+        fn dist(&self, _: &Pt, _: &Pt) -> i64 {
+            10
+        }
+    }
+
+    #[test]
+    fn test_weighted_traversals() {
+        let maze = TollMaze(Maze::new());
+        assert_eq!(bfs(&maze.0, START).count(), bfs(&maze, START).count());
+        assert_eq!(11, bfs_to(&maze, START, |n| *n == GOAL).unwrap().cost);
+        assert_eq!(
+            dfs(&maze.0, START, VisitPolicy::Once).count(),
+            dfs(&maze, START, VisitPolicy::Once).count()
+        );
+        assert_eq!(11, bidirectional_bfs(&maze, START, GOAL).unwrap().cost);
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::ops::Index;
+
+use itertools::Itertools;
+
+use crate::mapping::canvas::dynamic::Canvas;
+use crate::mapping::canvas::render::{Glyph, Render};
+use crate::mapping::grid::{GridCells, GridPoints, Neighbourhood};
+use crate::positioning::pt::Pt;
+
+/// An unbounded 2D grid where only cells that have been set are stored; all other cells
+/// have a default value.
+///
+/// The grid keeps track of the [bounds](Self::bounds) of the cells that have been set, which
+/// can be used to convert the grid to a [`Canvas`] or to [render](Self::render) it.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::sparse::SparseGrid;
+/// # use aoclp::positioning::pt::Pt;
+///
+/// let mut grid = SparseGrid::new('.');
+/// grid.insert(Pt::new(-1, 0), '#');
+/// *grid.get_mut(Pt::new(1, 1)) = '@';
+///
+/// assert_eq!('#', grid[Pt::new(-1, 0)]);
+/// assert_eq!('.', grid[Pt::new(100, 100)]);
+/// assert_eq!(Some((Pt::new(-1, 0), Pt::new(1, 1))), grid.bounds());
+/// assert_eq!("#..\n..@", grid.render().to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pt, T>,
+    default: T,
+    bounds: Option<(Pt, Pt)>,
+}
+
+impl<T> SparseGrid<T> {
+    /// Creates a new, empty grid where all cells have the given `default` value.
+    pub fn new(default: T) -> Self {
+        Self { cells: HashMap::new(), default, bounds: None }
+    }
+
+    /// Creates a grid from a [`Canvas`], whose top-left corner will be at position `(0, 0)`.
+    ///
+    /// All canvas pieces are stored in the grid, even those equal to `default`.
+    pub fn from_canvas(canvas: Canvas<T>, default: T) -> Self {
+        let mut grid = Self::new(default);
+        grid.extend(canvas.into_map::<i64>());
+        grid
+    }
+
+    /// Returns the default value of cells that have not been set.
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Returns the value of the cell at `pt`, or the default value if the cell has not been set.
+    pub fn get(&self, pt: Pt) -> &T {
+        self.cells.get(&pt).unwrap_or(&self.default)
+    }
+
+    /// Returns a mutable reference to the cell at `pt`, setting it to the default value
+    /// first if it has not been set.
+    pub fn get_mut(&mut self, pt: Pt) -> &mut T
+    where
+        T: Clone,
+    {
+        self.expand_bounds(pt);
+        self.cells.entry(pt).or_insert_with(|| self.default.clone())
+    }
+
+    /// Sets the value of the cell at `pt`, returning its previous value if it had been set.
+    pub fn insert(&mut self, pt: Pt, value: T) -> Option<T> {
+        self.expand_bounds(pt);
+        self.cells.insert(pt, value)
+    }
+
+    /// Resets the cell at `pt` to the default value, returning its previous value if it
+    /// had been set.
+    ///
+    /// This does not shrink the grid's [bounds](Self::bounds).
+    pub fn remove(&mut self, pt: Pt) -> Option<T> {
+        self.cells.remove(&pt)
+    }
+
+    /// Returns `true` if the cell at `pt` has been set.
+    pub fn contains(&self, pt: Pt) -> bool {
+        self.cells.contains_key(&pt)
+    }
+
+    /// Returns the number of cells that have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if no cell has been set.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the top-left and bottom-right corners (inclusive) of the smallest rectangle
+    /// containing all cells that have been set, or `None` if no cell has ever been set.
+    pub fn bounds(&self) -> Option<(Pt, Pt)> {
+        self.bounds
+    }
+
+    /// Returns an [iterator](Iterator) of the cells that have been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pt, &T)> {
+        self.cells.iter().map(|(pt, value)| (*pt, value))
+    }
+
+    /// Returns an [iterator](Iterator) of the neighbours of `pt`, along with their values.
+    pub fn neighbours(
+        &self,
+        pt: Pt,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = (Pt, &T)> {
+        neighbourhood.of(pt).map(|n| (n, self.get(n)))
+    }
+
+    /// Converts the grid to a [`Canvas`] cropped to the grid's [bounds](Self::bounds).
+    /// The canvas is empty if no cell has been set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::sparse::SparseGrid;
+    /// # use aoclp::positioning::pt::Pt;
+    ///
+    /// let grid: SparseGrid<i32> = [(Pt::new(5, 5), 1), (Pt::new(6, 4), 2)]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert_eq!(vec![vec![0, 2], vec![1, 0]], grid.to_canvas().into_rows());
+    /// ```
+    pub fn to_canvas(&self) -> Canvas<T>
+    where
+        T: Clone,
+    {
+        Canvas::from_matrix(self.rows().map(|row| row.cloned().collect_vec()))
+    }
+
+    /// Returns a [`Render`] of the cells within the grid's [bounds](Self::bounds).
+    ///
+    /// [Paths](Render::path) and [marks](Render::mark) use grid coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::sparse::SparseGrid;
+    /// # use aoclp::positioning::pt::Pt;
+    ///
+    /// let mut grid = SparseGrid::new(false);
+    /// grid.insert(Pt::new(-2, -1), true);
+    /// grid.insert(Pt::new(0, 0), true);
+    /// let render = grid.render().mark(Pt::new(-1, 0), '@');
+    /// assert_eq!("#..\n.@#", render.to_string());
+    /// ```
+    pub fn render(&self) -> Render<'_, T>
+    where
+        T: Glyph,
+    {
+        self.render_with(T::glyph)
+    }
+
+    /// Returns a [`Render`] of the cells within the grid's [bounds](Self::bounds), using
+    /// a closure to convert every cell to a `char`.
+    pub fn render_with<'a, F>(&'a self, glyph: F) -> Render<'a, T>
+    where
+        F: Fn(&T) -> char + 'a,
+    {
+        let (min, max) = self.bounds.unwrap_or((Pt::new(0, 0), Pt::new(-1, -1)));
+        let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+        Render::from_fn(
+            width,
+            height,
+            move |x, y| self.get(min + Pt::new(x as i64, y as i64)),
+            glyph,
+        )
+        .origin(min)
+    }
+
+    fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (min, max) = self.bounds.unwrap_or((Pt::new(0, 0), Pt::new(-1, -1)));
+        (min.y..=max.y).map(move |y| (min.x..=max.x).map(move |x| self.get(Pt::new(x, y))))
+    }
+
+    fn expand_bounds(&mut self, pt: Pt) {
+        self.bounds = Some(match self.bounds {
+            None => (pt, pt),
+            Some((min, max)) => (
+                Pt::new(min.x.min(pt.x), min.y.min(pt.y)),
+                Pt::new(max.x.max(pt.x), max.y.max(pt.y)),
+            ),
+        });
+    }
+}
+
+impl<T> Default for SparseGrid<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Extend<(Pt, T)> for SparseGrid<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Pt, T)>,
+    {
+        for (pt, value) in iter {
+            self.insert(pt, value);
+        }
+    }
+}
+
+impl<T> FromIterator<(Pt, T)> for SparseGrid<T>
+where
+    T: Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Pt, T)>,
+    {
+        let mut grid = Self::default();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Index<Pt> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: Pt) -> &Self::Output {
+        self.get(index)
+    }
+}
+
+impl<T> GridCells<Pt> for SparseGrid<T> {
+    type Value = T;
+
+    /// Returns the value of the cell at `pt` if it is within the grid's [bounds](SparseGrid::bounds).
+    fn cell(&self, pt: &Pt) -> Option<&Self::Value> {
+        let (min, max) = self.bounds?;
+        pt.within(min.x..=max.x, min.y..=max.y)
+            .then(|| self.get(*pt))
+    }
+}
+
+impl<T> GridPoints<Pt> for SparseGrid<T> {
+    /// Returns all points within the grid's [bounds](SparseGrid::bounds), in row-major order.
+    fn points(&self) -> impl Iterator<Item = Pt> {
+        let (min, max) = self.bounds.unwrap_or((Pt::new(0, 0), Pt::new(-1, -1)));
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Pt::new(x, y)))
+    }
+}
+
+impl<T> From<SparseGrid<T>> for HashMap<Pt, T> {
+    fn from(value: SparseGrid<T>) -> Self {
+        value.cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::dij;
+    use crate::mapping::grid::GridGraph;
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = SparseGrid::new(0);
+        assert_eq!(None, grid.bounds());
+        assert_eq!("", grid.render_with(|_| '#').to_string());
+        assert_eq!(0, grid.to_canvas().count());
+
+        grid.insert(Pt::new(2, -3), 1);
+        assert_eq!(Some((Pt::new(2, -3), Pt::new(2, -3))), grid.bounds());
+        *grid.get_mut(Pt::new(-1, 0)) += 2;
+        assert_eq!(Some((Pt::new(-1, -3), Pt::new(2, 0))), grid.bounds());
+        assert_eq!(2, grid.len());
+
+        assert_eq!(Some(2), grid.remove(Pt::new(-1, 0)));
+        assert_eq!(0, grid[Pt::new(-1, 0)]);
+        assert_eq!(Some((Pt::new(-1, -3), Pt::new(2, 0))), grid.bounds());
+        let render = grid.render_with(|v| if *v > 0 { '#' } else { '.' });
+        assert_eq!("...#\n....\n....\n....", render.to_string());
+        let render = render.path([Pt::new(-1, 0), Pt::new(-1, -1), Pt::new(0, -1)]);
+        assert_eq!("...#\n....\n→→..\n↑...", render.to_string());
+    }
+
+    #[test]
+    fn test_canvas() {
+        let canvas = Canvas::from_lines(["#.", ".#"], |c| c == '#');
+        let grid = SparseGrid::from_canvas(canvas.clone(), false);
+        assert_eq!(4, grid.len());
+        assert!(grid[Pt::new(1, 1)]);
+        assert_eq!(canvas, grid.to_canvas());
+
+        let neighbours = grid
+            .neighbours(Pt::new(0, 0), Neighbourhood::Eight)
+            .filter(|(_, v)| **v)
+            .map(|(pt, _)| pt)
+            .collect_vec();
+        assert_eq!(vec![Pt::new(1, 1)], neighbours);
+    }
+
+    #[test]
+    fn test_grid_graph() {
+        let grid: SparseGrid<bool> = [(Pt::new(0, 0), true), (Pt::new(2, 2), true)]
+            .into_iter()
+            .collect();
+        let graph = GridGraph::new(&grid, Neighbourhood::Four, |_: &bool, to: &bool| !to);
+        let output = dij::build(&graph, Pt::new(0, 0));
+        assert_eq!(Some(&3), output.dist.get(&Pt::new(2, 1)));
+        assert_eq!(None, output.dist.get(&Pt::new(3, 2)));
+        assert_eq!(8, output.dist.len());
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::mapping::dij;
+use crate::mapping::dij::{Cost, Graph};
+
+/// Kind of route to look for when solving a [travelling salesman problem](DistanceMatrix).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Route {
+    /// Route that visits every node once and stops at the last one.
+    Open,
+    /// Route that visits every node once, then returns to its starting point.
+    Closed,
+}
+
+/// Route through all nodes of a [`DistanceMatrix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<T, C = usize> {
+    /// Total cost of the route.
+    pub cost: C,
+    /// Nodes in the order in which they are visited. For [closed routes](Route::Closed),
+    /// the starting node is repeated at the end.
+    pub order: Vec<T>,
+}
+
+/// Matrix of the distances between each pair of a set of key nodes.
+///
+/// Can be used to find the shortest or longest route visiting all key nodes, using the
+/// [Held-Karp algorithm](https://en.wikipedia.org/wiki/Held%E2%80%93Karp_algorithm).
+/// Since this algorithm runs in `O(2ⁿn²)`, it's only practical for up to ~20 nodes.
+///
+/// # Example
+///
+/// ```
+/// # use aoclp::mapping::tsp::{DistanceMatrix, Route};
+///
+/// let distances =
+///     [("London", "Dublin", 464), ("London", "Belfast", 518), ("Dublin", "Belfast", 141)];
+/// let matrix = DistanceMatrix::from_fn(["London", "Dublin", "Belfast"], |a, b| {
+///     distances
+///         .iter()
+///         .find(|(from, to, _)| (from, to) == (a, b) || (from, to) == (b, a))
+///         .map(|(_, _, d)| *d)
+/// });
+///
+/// let tour = matrix.shortest_tour(None, Route::Open).unwrap();
+/// assert_eq!(605, tour.cost);
+/// assert_eq!(vec!["Belfast", "Dublin", "London"], tour.order);
+/// assert_eq!(982, matrix.longest_tour(None, Route::Open).unwrap().cost);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix<T, C = usize> {
+    nodes: Vec<T>,
+    indices: HashMap<T, usize>,
+    dist: Vec<Vec<Option<C>>>,
+}
+
+impl<T, C> DistanceMatrix<T, C>
+where
+    T: Clone + Eq + Hash,
+    C: Cost,
+{
+    /// Builds a distance matrix by running [Dijkstra's algorithm](dij) from each of
+    /// the given key `nodes` in `graph`.
+    pub fn from_graph<G, I>(graph: &G, nodes: I) -> Self
+    where
+        G: Graph<T, C>,
+        I: IntoIterator<Item = T>,
+    {
+        let nodes: Vec<T> = nodes.into_iter().collect();
+        let dist = nodes
+            .iter()
+            .map(|from| {
+                let mut remaining: HashSet<&T> = nodes.iter().collect();
+                let output = dij::build_until(graph, from.clone(), |n| {
+                    remaining.remove(n);
+                    remaining.is_empty()
+                });
+                nodes
+                    .iter()
+                    .map(|to| output.dist.get(to).copied())
+                    .collect()
+            })
+            .collect();
+
+        Self::new(nodes, dist)
+    }
+
+    /// Builds a distance matrix by calling `f` for each pair of distinct `nodes`.
+    ///
+    /// `f` should return `None` if there is no route between two nodes.
+    pub fn from_fn<I, F>(nodes: I, mut f: F) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T, &T) -> Option<C>,
+    {
+        let nodes: Vec<T> = nodes.into_iter().collect();
+        let dist = nodes
+            .iter()
+            .map(|a| {
+                nodes
+                    .iter()
+                    .map(|b| if a == b { Some(C::zero()) } else { f(a, b) })
+                    .collect()
+            })
+            .collect();
+
+        Self::new(nodes, dist)
+    }
+
+    /// Returns the key nodes of the matrix.
+    pub fn nodes(&self) -> &[T] {
+        &self.nodes
+    }
+
+    /// Returns the distance from `a` to `b`, or `None` if there is no route between
+    /// them or one of them is not a key node.
+    pub fn dist(&self, a: &T, b: &T) -> Option<C> {
+        self.dist[*self.indices.get(a)?][*self.indices.get(b)?]
+    }
+
+    /// Returns the shortest route visiting all key nodes, or `None` if there is none.
+    ///
+    /// If `start` is `None`, the route can start at any node.
+    pub fn shortest_tour(&self, start: Option<&T>, route: Route) -> Option<Tour<T, C>> {
+        self.held_karp(start, route, |a, b| a < b)
+    }
+
+    /// Returns the longest route visiting all key nodes, or `None` if there is none.
+    ///
+    /// If `start` is `None`, the route can start at any node.
+    pub fn longest_tour(&self, start: Option<&T>, route: Route) -> Option<Tour<T, C>> {
+        self.held_karp(start, route, |a, b| a > b)
+    }
+
+    fn new(nodes: Vec<T>, dist: Vec<Vec<Option<C>>>) -> Self {
+        let indices = nodes
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, n)| (n, i))
+            .collect();
+        Self { nodes, indices, dist }
+    }
+
+    fn held_karp<F>(&self, start: Option<&T>, route: Route, better: F) -> Option<Tour<T, C>>
+    where
+        F: Fn(C, C) -> bool,
+    {
+        let n = self.nodes.len();
+        if n == 0 {
+            return None;
+        }
+        let start = match (start, route) {
+            (Some(start), _) => Some(*self.indices.get(start)?),
+            (None, Route::Closed) => Some(0),
+            (None, Route::Open) => None,
+        };
+
+        // costs[mask][last]: best cost of a route visiting the nodes in `mask`, ending at `last`.
+        let full = (1usize << n) - 1;
+        let mut costs: Vec<Vec<Option<C>>> = vec![vec![None; n]; full + 1];
+        let mut prev = vec![vec![usize::MAX; n]; full + 1];
+        for i in (0..n).filter(|i| start.is_none_or(|s| s == *i)) {
+            costs[1 << i][i] = Some(C::zero());
+        }
+
+        for mask in 1..=full {
+            for last in 0..n {
+                let Some(cost) = costs[mask][last] else {
+                    continue;
+                };
+                for next in (0..n).filter(|next| mask & (1 << next) == 0) {
+                    let Some(d) = self.dist[last][next] else {
+                        continue;
+                    };
+                    let (next_mask, next_cost) = (mask | (1 << next), cost + d);
+                    if costs[next_mask][next].is_none_or(|c| better(next_cost, c)) {
+                        costs[next_mask][next] = Some(next_cost);
+                        prev[next_mask][next] = last;
+                    }
+                }
+            }
+        }
+
+        let (cost, last) = (0..n)
+            .filter_map(|last| {
+                let cost = costs[full][last]?;
+                match route {
+                    Route::Open => Some((cost, last)),
+                    Route::Closed => Some((cost + self.dist[last][start.unwrap()]?, last)),
+                }
+            })
+            .reduce(|best, candidate| if better(candidate.0, best.0) { candidate } else { best })?;
+
+        let mut order = Vec::with_capacity(n + 1);
+        let (mut mask, mut node) = (full, last);
+        while node != usize::MAX {
+            order.push(self.nodes[node].clone());
+            (mask, node) = (mask & !(1 << node), prev[mask][node]);
+        }
+        order.reverse();
+        if route == Route::Closed {
+            order.push(order[0].clone());
+        }
+
+        Some(Tour { cost, order })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::canvas::dynamic::Canvas;
+    use crate::mapping::grid::{GridGraph, Neighbourhood};
+    use crate::positioning::pt::Pt;
+
+    #[test]
+    fn test_from_graph() {
+        let canvas = Canvas::from_lines(
+            ["###########", "#0.1.....2#", "#.#######.#", "#4.......3#", "###########"],
+            |c| c,
+        );
+        let graph = GridGraph::new(&canvas, Neighbourhood::Four, |_: &char, to: &char| *to != '#');
+        let pois = (0..5).map(|i| {
+            let c = char::from_digit(i, 10).unwrap();
+            canvas
+                .rows()
+                .enumerate()
+                .find_map(|(y, row)| {
+                    row.iter()
+                        .position(|t| *t == c)
+                        .map(|x| Pt::new(x as i64, y as i64))
+                })
+                .unwrap()
+        });
+        let matrix = DistanceMatrix::from_graph(&graph, pois);
+        let (zero, three) = (matrix.nodes()[0], matrix.nodes()[3]);
+        assert_eq!(Some(10), matrix.dist(&zero, &three));
+
+        let tour = matrix.shortest_tour(Some(&zero), Route::Open).unwrap();
+        assert_eq!(14, tour.cost);
+        assert_eq!(zero, tour.order[0]);
+        assert_eq!(5, tour.order.len());
+
+        let tour = matrix.shortest_tour(Some(&zero), Route::Closed).unwrap();
+        assert_eq!(20, tour.cost);
+        assert_eq!(6, tour.order.len());
+        assert_eq!(Some(&zero), tour.order.last());
+    }
+
+    #[test]
+    fn test_unreachable() {
+        // Nodes 0 and 1 can only be reached from 2, so closed tours are impossible
+        let matrix = DistanceMatrix::from_fn(0..3, |a, b| match (a, b) {
+            (2, 0) => Some(1),
+            (2, 1) => Some(2),
+            (0, 1) | (1, 0) => Some(5),
+            _ => None,
+        });
+        assert_eq!(None, matrix.dist(&0, &2));
+        assert_eq!(None, matrix.dist(&0, &3));
+
+        let tour = matrix.shortest_tour(None, Route::Open).unwrap();
+        assert_eq!(Tour { cost: 6, order: vec![2, 0, 1] }, tour);
+        assert_eq!(None, matrix.shortest_tour(Some(&0), Route::Open));
+        assert_eq!(None, matrix.shortest_tour(None, Route::Closed));
+        assert_eq!(None, matrix.longest_tour(Some(&5), Route::Open));
+    }
+}
@@ -10,6 +10,7 @@ use std::sync::OnceLock;
 use gratte::IntoEnumIterator;
 
 use crate::captures::CapturesHelper;
+use crate::num::traits::Euclid;
 use crate::num::{Signed, Zero, zero};
 use crate::positioning::Point;
 use crate::positioning::direction::MovementDirection;
@@ -63,6 +64,26 @@ where
     }
 }
 
+impl<T> Pt<T>
+where
+    T: Euclid,
+{
+    /// Wraps the point around a `width` by `height` area whose top-left corner is at `(0, 0)`,
+    /// as if the area was a torus. Negative coordinates wrap around from the other side.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::positioning::pt::Pt;
+    ///
+    /// assert_eq!(Pt::new(1, 2), Pt::new(6, 2).wrap(5, 3));
+    /// assert_eq!(Pt::new(4, 1), Pt::new(-1, -5).wrap(5, 3));
+    /// ```
+    pub fn wrap(&self, width: T, height: T) -> Self {
+        Self::new(self.x.rem_euclid(&width), self.y.rem_euclid(&height))
+    }
+}
+
 impl<T> Point for Pt<T> {
     type Coord = T;
 }
@@ -1,6 +1,8 @@
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, Neg};
 
+use crate::mapping::canvas::dynamic::Canvas;
+use crate::num::traits::Euclid;
 use crate::num::{One, Zero, zero};
 use crate::positioning::direction::four_points::Direction4;
 use crate::positioning::direction::{Direction, MovementDirection};
@@ -71,6 +73,128 @@ where
     {
         Self { position: self.position + self.direction.displacement(), ..*self }
     }
+
+    /// Advances the [`Turtle`] one step in the direction it is currently facing, within
+    /// an area with [edge transitions](EdgeTransition). If the step would take the turtle
+    /// outside the area, the area determines where it ends up instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::positioning::direction::four_points::Direction4;
+    /// # use aoclp::positioning::pt::Pt;
+    /// # use aoclp::positioning::turtle::{EdgeTransition, Turtle};
+    ///
+    /// // A corridor where the turtle bounces back from the walls.
+    /// struct Corridor(i64);
+    ///
+    /// impl EdgeTransition<i64, Direction4> for Corridor {
+    ///     fn contains(&self, pt: &Pt) -> bool {
+    ///         pt.y == 0 && (0..self.0).contains(&pt.x)
+    ///     }
+    ///
+    ///     fn cross(&self, turtle: &Turtle) -> Turtle {
+    ///         turtle.turn_around().advance()
+    ///     }
+    /// }
+    ///
+    /// let turtle = Turtle::new(Pt::new(1, 0), Direction4::Right);
+    /// let turtle = turtle.advance_with(&Corridor(2));
+    /// assert_eq!(Turtle::new(Pt::new(0, 0), Direction4::Left), turtle);
+    /// ```
+    pub fn advance_with<E>(&self, edges: &E) -> Self
+    where
+        T: Zero + One + Neg<Output = T> + Add<Output = T>,
+        Pt<T>: Copy,
+        E: EdgeTransition<T, Dir>,
+    {
+        let next = self.advance();
+        if edges.contains(&next.position) { next } else { edges.cross(self) }
+    }
+}
+
+impl<Dir> Turtle<i64, Dir>
+where
+    Dir: MovementDirection<i64> + Copy,
+{
+    /// Advances the [`Turtle`] one step in the direction it is currently facing, wrapping
+    /// around the edges of `canvas` like on a [`Torus`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use aoclp::mapping::canvas::dynamic::Canvas;
+    /// # use aoclp::positioning::direction::four_points::Direction4;
+    /// # use aoclp::positioning::pt::Pt;
+    /// # use aoclp::positioning::turtle::Turtle;
+    ///
+    /// let canvas = Canvas::of(3, 2, '.');
+    /// let turtle = Turtle::new(Pt::new(1, 0), Direction4::Up).advance_wrapping(&canvas);
+    /// assert_eq!(Pt::new(1, 1), turtle.position);
+    /// ```
+    pub fn advance_wrapping<V>(&self, canvas: &Canvas<V>) -> Self {
+        self.advance_with(&Torus::from(canvas))
+    }
+}
+
+/// Trait implemented by bounded areas in which a [`Turtle`] can move, determining where
+/// the turtle ends up when it moves past one of the area's edges.
+///
+/// Used by [`Turtle::advance_with`]. [`Torus`] wraps around edges; puzzles with custom wrap
+/// rules (like folding a map into a cube) can implement their own mapping.
+pub trait EdgeTransition<T, Dir> {
+    /// Returns `true` if `pt` is within the area.
+    fn contains(&self, pt: &Pt<T>) -> bool;
+
+    /// Given a `turtle` about to move past an edge of the area, returns the turtle's state
+    /// after the move. Both position and direction can be changed.
+    fn cross(&self, turtle: &Turtle<T, Dir>) -> Turtle<T, Dir>;
+}
+
+/// A `width` by `height` area whose top-left corner is at `(0, 0)` and whose edges
+/// [wrap around](Pt::wrap), like on a torus.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Torus<T = i64> {
+    pub width: T,
+    pub height: T,
+}
+
+impl<T> Torus<T> {
+    /// Returns a new [`Torus`] of the given size.
+    pub fn new(width: T, height: T) -> Self {
+        Self { width, height }
+    }
+}
+
+impl<V> From<&Canvas<V>> for Torus {
+    /// Returns a [`Torus`] the size of the given canvas.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas is empty, since nothing can wrap around it.
+    fn from(value: &Canvas<V>) -> Self {
+        assert!(value.count() != 0, "cannot wrap around an empty canvas");
+        Self::new(value.width() as i64, value.height() as i64)
+    }
+}
+
+impl<T, Dir> EdgeTransition<T, Dir> for Torus<T>
+where
+    T: Euclid + Zero + One + Neg<Output = T> + Add<Output = T> + PartialOrd + Copy,
+    Dir: MovementDirection<T> + Copy,
+{
+    fn contains(&self, pt: &Pt<T>) -> bool {
+        pt.within(zero()..self.width, zero()..self.height)
+    }
+
+    fn cross(&self, turtle: &Turtle<T, Dir>) -> Turtle<T, Dir> {
+        let position = turtle.advance().position.wrap(self.width, self.height);
+        Turtle { position, ..*turtle }
+    }
 }
 
 impl<T, Dir> Display for Turtle<T, Dir>
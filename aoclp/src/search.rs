@@ -0,0 +1,358 @@
+//! Generic searches over puzzle states.
+//!
+//! Puzzles are described by implementing [`StateSpace`]; the search functions in this
+//! module then look for the best [goal](StateSpace::is_goal) state, e.g. the goal state
+//! with the highest [score](StateSpace::score). To minimize a cost instead, use
+//! [`Reverse`](std::cmp::Reverse) as score.
+//!
+//! All searches memoize visited states, so a state reachable in several ways is only
+//! explored once. This means that a state's score must only depend on the state itself.
+
+use std::collections::{BinaryHeap, HashSet};
+use std::hash::Hash;
+
+/// Trait describing the states of a puzzle and how to move between them.
+pub trait StateSpace {
+    /// Type of states explored by the search.
+    type State: Clone + Eq + Hash;
+    /// Type of score given to states; higher is better.
+    type Score: Copy + Ord;
+
+    /// Returns the states that can be reached from `state` in one move.
+    fn successors(&self, state: &Self::State) -> impl Iterator<Item = Self::State>;
+
+    /// Returns the score of `state`.
+    fn score(&self, state: &Self::State) -> Self::Score;
+
+    /// Returns `true` if `state` is a valid solution.
+    ///
+    /// By default, every state is a solution.
+    fn is_goal(&self, state: &Self::State) -> bool {
+        let _ = state;
+        true
+    }
+
+    /// Returns an upper bound on the score of any goal state reachable from `state`,
+    /// including `state` itself, or `None` if unknown.
+    ///
+    /// Bounds are used to prune hopeless states and to prioritize promising ones; they
+    /// must never underestimate, otherwise the best solution might be missed.
+    fn bound(&self, state: &Self::State) -> Option<Self::Score> {
+        let _ = state;
+        None
+    }
+}
+
+/// Statistics about a search, useful to tune [bounds](StateSpace::bound).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of states whose successors were generated.
+    pub expanded: usize,
+    /// Number of successor states generated.
+    pub generated: usize,
+    /// Number of states skipped because they had already been visited.
+    pub duplicates: usize,
+    /// Number of states skipped because their bound showed they could not improve on
+    /// the best solution, or because they fell outside the beam.
+    pub pruned: usize,
+}
+
+/// Result of a search.
+#[derive(Debug, Clone)]
+pub struct Outcome<S, C> {
+    /// Best goal state found, along with its score, or `None` if no goal state was found.
+    pub best: Option<(S, C)>,
+    /// Statistics about the search.
+    pub stats: Stats,
+}
+
+impl<S, C> Outcome<S, C> {
+    /// Returns the score of the best goal state found, if any.
+    pub fn best_score(&self) -> Option<C>
+    where
+        C: Copy,
+    {
+        self.best.as_ref().map(|(_, score)| *score)
+    }
+}
+
+/// Performs a depth-first search of all states reachable from `start`, returning the
+/// best goal state found.
+///
+/// States whose [bound](StateSpace::bound) is not better than the best solution found
+/// so far are pruned.
+pub fn branch_and_bound<SP>(space: &SP, start: SP::State) -> Outcome<SP::State, SP::Score>
+where
+    SP: StateSpace,
+{
+    let mut search = Search::new(space);
+    let mut stack = vec![start.clone()];
+    search.visited.insert(start);
+
+    while let Some(state) = stack.pop() {
+        search.consider(&state);
+        if search.cannot_improve(&state) {
+            search.stats.pruned += 1;
+            continue;
+        }
+        stack.extend(search.expand(&state));
+    }
+
+    search.into_outcome()
+}
+
+/// Performs a [beam search](https://en.wikipedia.org/wiki/Beam_search) from `start`:
+/// states are explored one depth level at a time, only keeping the `width` most promising
+/// states of each level (as determined by their [bound](StateSpace::bound), or their score
+/// if they have none).
+///
+/// Much faster than an exhaustive search, but might miss the best solution.
+pub fn beam_search<SP>(space: &SP, start: SP::State, width: usize) -> Outcome<SP::State, SP::Score>
+where
+    SP: StateSpace,
+{
+    let mut search = Search::new(space);
+    let mut level = vec![start.clone()];
+    search.visited.insert(start);
+
+    while !level.is_empty() {
+        level.iter().for_each(|state| search.consider(state));
+
+        let mut next = Vec::new();
+        for state in &level {
+            for successor in search.expand(state) {
+                next.push((search.priority(&successor), successor));
+            }
+        }
+        if next.len() > width {
+            next.select_nth_unstable_by(width, |(a, _), (b, _)| b.cmp(a));
+            search.stats.pruned += next.len() - width;
+            next.truncate(width);
+        }
+        level = next.into_iter().map(|(_, state)| state).collect();
+    }
+
+    search.into_outcome()
+}
+
+/// Performs a best-first search from `start`, always exploring the most promising state
+/// first (as determined by its [bound](StateSpace::bound), or its score if it has none),
+/// and stopping as soon as no remaining state can beat the best solution found.
+///
+/// This finds the best solution as long as bounds never underestimate. When states have
+/// no bound, this requires scores to never improve when moving from a state to its
+/// successors, like when minimizing a number of moves; the search then stops at the first
+/// goal state reached.
+pub fn best_first<SP>(space: &SP, start: SP::State) -> Outcome<SP::State, SP::Score>
+where
+    SP: StateSpace,
+{
+    let mut search = Search::new(space);
+    let mut q = BinaryHeap::from([Prioritized(search.priority(&start), start.clone())]);
+    search.visited.insert(start);
+
+    while let Some(Prioritized(priority, state)) = q.pop() {
+        search.consider(&state);
+        if search
+            .best
+            .as_ref()
+            .is_some_and(|(_, best)| *best >= priority)
+        {
+            break;
+        }
+        for successor in search.expand(&state) {
+            q.push(Prioritized(search.priority(&successor), successor));
+        }
+    }
+
+    search.into_outcome()
+}
+
+struct Search<'a, SP>
+where
+    SP: StateSpace,
+{
+    space: &'a SP,
+    visited: HashSet<SP::State>,
+    best: Option<(SP::State, SP::Score)>,
+    stats: Stats,
+}
+
+impl<'a, SP> Search<'a, SP>
+where
+    SP: StateSpace,
+{
+    fn new(space: &'a SP) -> Self {
+        Self { space, visited: HashSet::new(), best: None, stats: Stats::default() }
+    }
+
+    /// Records `state` as the best solution if it is a goal state that beats the current best.
+    fn consider(&mut self, state: &SP::State) {
+        if self.space.is_goal(state) {
+            let score = self.space.score(state);
+            if self.best.as_ref().is_none_or(|(_, best)| score > *best) {
+                self.best = Some((state.clone(), score));
+            }
+        }
+    }
+
+    fn cannot_improve(&self, state: &SP::State) -> bool {
+        match (&self.best, self.space.bound(state)) {
+            (Some((_, best)), Some(bound)) => bound <= *best,
+            _ => false,
+        }
+    }
+
+    fn priority(&self, state: &SP::State) -> SP::Score {
+        self.space
+            .bound(state)
+            .unwrap_or_else(|| self.space.score(state))
+    }
+
+    /// Returns the successors of `state` that were not visited yet.
+    fn expand(&mut self, state: &SP::State) -> Vec<SP::State> {
+        self.stats.expanded += 1;
+        self.space
+            .successors(state)
+            .filter(|s| {
+                self.stats.generated += 1;
+                let new = self.visited.insert(s.clone());
+                if !new {
+                    self.stats.duplicates += 1;
+                }
+                new
+            })
+            .collect()
+    }
+
+    fn into_outcome(self) -> Outcome<SP::State, SP::Score> {
+        Outcome { best: self.best, stats: self.stats }
+    }
+}
+
+/// Entry in a priority queue, ordered by priority only.
+struct Prioritized<P, S>(P, S);
+
+impl<P: Ord, S> PartialEq for Prioritized<P, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<P: Ord, S> Eq for Prioritized<P, S> {}
+
+impl<P: Ord, S> PartialOrd for Prioritized<P, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord, S> Ord for Prioritized<P, S> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Reverse;
+
+    use super::*;
+
+    /// 0/1 knapsack: items are `(weight, value)`, states are `(next item, weight, value)`.
+    struct Knapsack {
+        items: Vec<(u32, u32)>,
+        capacity: u32,
+    }
+
+    impl StateSpace for Knapsack {
+        type State = (usize, u32, u32);
+        type Score = u32;
+
+        fn successors(&self, state: &Self::State) -> impl Iterator<Item = Self::State> {
+            let &(i, weight, value) = state;
+            let item = self.items.get(i);
+            let skip = item.map(|_| (i + 1, weight, value));
+            let take = item
+                .filter(|(w, _)| weight + w <= self.capacity)
+                .map(|(w, v)| (i + 1, weight + w, value + v));
+            take.into_iter().chain(skip)
+        }
+
+        fn score(&self, state: &Self::State) -> Self::Score {
+            state.2
+        }
+
+        fn bound(&self, state: &Self::State) -> Option<Self::Score> {
+            Some(state.2 + self.items[state.0..].iter().map(|(_, v)| v).sum::<u32>())
+        }
+    }
+
+    fn knapsack() -> Knapsack {
+        Knapsack {
+            items: vec![(12, 4), (2, 2), (1, 1), (1, 2), (4, 10), (3, 7), (9, 9), (5, 5)],
+            capacity: 15,
+        }
+    }
+
+    /// Reach a number by either adding 1 or doubling; minimize the number of moves.
+    struct Doubling(u32);
+
+    impl StateSpace for Doubling {
+        type State = (u32, u32);
+        type Score = Reverse<u32>;
+
+        fn successors(&self, state: &Self::State) -> impl Iterator<Item = Self::State> {
+            let &(n, moves) = state;
+            [(n + 1, moves + 1), (n * 2, moves + 1)]
+                .into_iter()
+                .filter(|(n, _)| *n <= self.0)
+        }
+
+        fn score(&self, state: &Self::State) -> Self::Score {
+            Reverse(state.1)
+        }
+
+        fn is_goal(&self, state: &Self::State) -> bool {
+            state.0 == self.0
+        }
+    }
+
+    #[test]
+    fn test_branch_and_bound() {
+        let outcome = branch_and_bound(&knapsack(), (0, 0, 0));
+        assert_eq!(Some(26), outcome.best_score());
+        assert_eq!(15, outcome.best.unwrap().0.1);
+        assert!(outcome.stats.pruned > 0);
+        assert!(outcome.stats.expanded + outcome.stats.pruned <= outcome.stats.generated + 1);
+
+        let outcome = branch_and_bound(&Doubling(100), (1, 0));
+        assert_eq!(Some(Reverse(8)), outcome.best_score());
+    }
+
+    #[test]
+    fn test_beam_search() {
+        let outcome = beam_search(&knapsack(), (0, 0, 0), 1_000);
+        assert_eq!(Some(26), outcome.best_score());
+        assert_eq!(0, outcome.stats.pruned);
+
+        let outcome = beam_search(&knapsack(), (0, 0, 0), 2);
+        assert!(outcome.best_score().is_some_and(|score| score <= 26));
+        assert!(outcome.stats.pruned > 0);
+    }
+
+    #[test]
+    fn test_best_first() {
+        let outcome = best_first(&Doubling(100), (1, 0));
+        let (state, score) = outcome.best.unwrap();
+        assert_eq!((100, 8), state);
+        assert_eq!(Reverse(8), score);
+
+        let outcome = best_first(&knapsack(), (0, 0, 0));
+        assert_eq!(Some(26), outcome.best_score());
+        let exhaustive = branch_and_bound(&knapsack(), (0, 0, 0));
+        assert!(outcome.stats.expanded < exhaustive.stats.expanded);
+
+        assert!(best_first(&Doubling(0), (1, 0)).best.is_none());
+    }
+}
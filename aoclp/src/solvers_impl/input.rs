@@ -1,11 +1,31 @@
+use std::collections::HashMap;
+use std::fs::{File, create_dir_all, read_to_string, write};
+use std::io::{BufRead, BufReader, Cursor};
+use std::iter::from_fn;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use aocf::Aoc;
 use itertools::Itertools;
 
-use crate::anyhow::anyhow;
+use crate::anyhow::{Context, anyhow};
+use crate::mapping::canvas::TryFromLines;
+use crate::mapping::canvas::dynamic::Canvas;
+use crate::positioning::pt::Pt;
 
 pub const DEFAULT_DATA_SEPARATORS: &[char] = &[' ', '\t', '|', ',', ':'];
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// A block of aligned text columns, as returned by [`Input::into_columns`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnBlock {
+    /// Index of the block's first character column in the input lines.
+    pub start: usize,
+    /// Text of the block, one string per input line, padded with spaces to the block's width.
+    pub rows: Vec<String>,
+    /// Text of the block, one string per character column from left to right, each read
+    /// from top to bottom.
+    pub columns: Vec<String>,
+}
 
 #[derive(Debug)]
 pub struct Input<'a> {
@@ -13,12 +33,26 @@ pub struct Input<'a> {
     day: Option<u32>,
     force: bool,
     data: Option<String>,
+    file: Option<PathBuf>,
+    base_url: String,
+    session: Option<String>,
+    cache_dir: Option<PathBuf>,
     separators: &'a [char],
 }
 
 impl<'a> Input<'a> {
     pub fn year(year: i32) -> Self {
-        Self { year, day: None, force: false, data: None, separators: DEFAULT_DATA_SEPARATORS }
+        Self {
+            year,
+            day: None,
+            force: false,
+            data: None,
+            file: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+            cache_dir: None,
+            separators: DEFAULT_DATA_SEPARATORS,
+        }
     }
 
     pub fn day(mut self, day: u32) -> Self {
@@ -40,29 +74,46 @@ impl<'a> Input<'a> {
         input
     }
 
+    pub fn from_file<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let mut input = Self::year(0);
+        input.file = Some(path.as_ref().to_path_buf());
+        input
+    }
+
+    pub fn base_url<S>(mut self, base_url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn session<S>(mut self, session: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.session = Some(session.into());
+        self
+    }
+
+    pub fn cache_dir<P>(mut self, cache_dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.cache_dir = Some(cache_dir.as_ref().to_path_buf());
+        self
+    }
+
     pub fn separators(mut self, separators: &'a [char]) -> Self {
         self.separators = separators;
         self
     }
 
     pub fn get(mut self) -> crate::Result<Self> {
-        let mut aoc = Aoc::new()
-            .year(Some(self.year))
-            .day(Some(self.day.ok_or(anyhow!("day not set"))?))
-            .parse_cli(false)
-            .init()
-            .map_err(|e| anyhow!(e))?;
-
-        self.data = Some(
-            aoc.get_input(self.force)
-                .map(|mut input| {
-                    if input.ends_with('\n') {
-                        input.remove(input.len() - 1);
-                    }
-                    input
-                })
-                .map_err(|e| anyhow!(e))?,
-        );
+        self.data = Some(strip_trailing_newline(self.fetch()?));
         Ok(self)
     }
 
@@ -70,13 +121,59 @@ impl<'a> Input<'a> {
         self.get().unwrap()
     }
 
+    /// Fetches the puzzle input from `{base_url}/{year}/day/{day}/input`, unless it is
+    /// already cached (and `force` is not set).
+    ///
+    /// If not set, the session and cache directory are those used by the `aocf` CLI
+    /// (see [`aocf_dir`]).
+    fn fetch(&self) -> crate::Result<String> {
+        let day = self.day.ok_or(anyhow!("day not set"))?;
+        let cache_file = self
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| aocf_dir().join("cache"))
+            .join(self.year.to_string())
+            .join(format!("day_{day:02}.txt"));
+        if !self.force && cache_file.exists() {
+            return read_to_string(&cache_file)
+                .with_context(|| format!("failed to read {}", cache_file.display()));
+        }
+
+        let session = match &self.session {
+            Some(session) => session.clone(),
+            None => aocf_session()?,
+        };
+        let url = format!("{}/{}/day/{day}/input", self.base_url.trim_end_matches('/'), self.year);
+        let input = ureq::get(&url)
+            .header("Cookie", format!("session={session}"))
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .and_then(|response| response.into_body().read_to_string())
+            .map_err(|e| match e {
+                ureq::Error::StatusCode(404) => {
+                    anyhow!("input not found for year {} day {day}", self.year)
+                },
+                ureq::Error::StatusCode(400 | 401 | 403) => {
+                    anyhow!("session rejected while fetching {url}")
+                },
+                e => anyhow!(e).context(format!("failed to fetch {url}")),
+            })?;
+
+        if let Some(dir) = cache_file.parent() {
+            create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        write(&cache_file, &input)
+            .with_context(|| format!("failed to write {}", cache_file.display()))?;
+
+        Ok(input)
+    }
+
     pub fn into<T>(self) -> crate::Result<T>
     where
         T: FromStr,
         <T as FromStr>::Err: std::fmt::Debug,
     {
-        self.data
-            .ok_or(anyhow!("data not set"))?
+        String::try_from(self)?
             .parse()
             .map_err(|e| anyhow!("failed to parse data: {e:?}"))
     }
@@ -236,6 +333,218 @@ impl<'a> Input<'a> {
         self.into_terrain().unwrap()
     }
 
+    pub fn into_canvas<T>(self) -> crate::Result<Canvas<T>>
+    where
+        T: From<char>,
+    {
+        Self::parse_canvas(String::try_from(self)?.lines(), |_, c| c.into())
+    }
+
+    pub fn safe_into_canvas<T>(self) -> Canvas<T>
+    where
+        T: From<char>,
+    {
+        self.into_canvas().unwrap()
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn into_grid_with_markers<T>(
+        self,
+        markers: &[char],
+        default: T,
+    ) -> crate::Result<(Canvas<T>, HashMap<char, Vec<Pt>>)>
+    where
+        T: From<char> + Clone,
+    {
+        let mut positions: HashMap<char, Vec<Pt>> = HashMap::new();
+        let canvas = Self::parse_canvas(String::try_from(self)?.lines(), |pt, c| {
+            if markers.contains(&c) {
+                positions.entry(c).or_default().push(pt);
+                default.clone()
+            } else {
+                c.into()
+            }
+        })?;
+
+        Ok((canvas, positions))
+    }
+
+    pub fn safe_into_grid_with_markers<T>(
+        self,
+        markers: &[char],
+        default: T,
+    ) -> (Canvas<T>, HashMap<char, Vec<Pt>>)
+    where
+        T: From<char> + Clone,
+    {
+        self.into_grid_with_markers(markers, default).unwrap()
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn into_labelled_canvases<C, T, F>(
+        self,
+        mut f: F,
+    ) -> crate::Result<(Vec<(String, C)>, String)>
+    where
+        C: TryFromLines<T>,
+        F: FnMut(char) -> T,
+    {
+        let data: String = self.try_into()?;
+        let mut lines = data.lines().peekable();
+
+        let mut canvases = Vec::new();
+        loop {
+            while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+            let Some(label) = lines.peek().and_then(|line| Self::grid_label(line)) else {
+                break;
+            };
+            lines.next();
+
+            let body = lines
+                .peeking_take_while(|line| !line.trim().is_empty())
+                .collect_vec();
+            let canvas = C::try_from_lines(body, &mut f)
+                .with_context(|| format!("failed to parse grid \"{label}\""))?;
+            canvases.push((label.to_string(), canvas));
+        }
+
+        Ok((canvases, lines.join("\n")))
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn safe_into_labelled_canvases<C, T, F>(self, f: F) -> (Vec<(String, C)>, String)
+    where
+        C: TryFromLines<T>,
+        F: FnMut(char) -> T,
+    {
+        self.into_labelled_canvases(f).unwrap()
+    }
+
+    pub fn into_columns(self) -> crate::Result<Vec<ColumnBlock>> {
+        let data: String = self.try_into()?;
+        let lines = data
+            .lines()
+            .map(|line| line.chars().collect_vec())
+            .collect_vec();
+        let width = lines.iter().map(Vec::len).max().unwrap_or_default();
+        let char_at = |line: &[char], col: usize| line.get(col).copied().unwrap_or(' ');
+
+        let is_gutter = |col: usize| lines.iter().all(|line| char_at(line, col) == ' ');
+        let blocks = (0..width)
+            .chunk_by(|col| is_gutter(*col))
+            .into_iter()
+            .filter(|(gutter, _)| !gutter)
+            .map(|(_, cols)| {
+                let cols = cols.collect_vec();
+                let rows = lines
+                    .iter()
+                    .map(|line| cols.iter().map(|col| char_at(line, *col)).collect())
+                    .collect();
+                let columns = cols
+                    .iter()
+                    .map(|col| lines.iter().map(|line| char_at(line, *col)).collect())
+                    .collect();
+                ColumnBlock { start: cols[0], rows, columns }
+            })
+            .collect();
+
+        Ok(blocks)
+    }
+
+    pub fn safe_into_columns(self) -> Vec<ColumnBlock> {
+        self.into_columns().unwrap()
+    }
+
+    pub fn lines_parsed<T>(self) -> crate::Result<impl Iterator<Item = crate::Result<(usize, T)>>>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Debug,
+    {
+        Ok(Self::numbered_lines(self.into_reader()?).map(|line| {
+            let (line_no, line) = line?;
+            line.parse()
+                .map(|value| (line_no, value))
+                .map_err(|e| anyhow!("failed to parse \"{line}\" at line {line_no}: {e:?}"))
+        }))
+    }
+
+    pub fn safe_lines_parsed<T>(self) -> impl Iterator<Item = (usize, T)>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Debug,
+    {
+        self.lines_parsed().unwrap().map(Result::unwrap)
+    }
+
+    pub fn records<T>(self) -> crate::Result<impl Iterator<Item = crate::Result<(usize, T)>>>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Debug,
+    {
+        let mut lines = Self::numbered_lines(self.into_reader()?);
+
+        Ok(from_fn(move || {
+            let mut start = None;
+            let mut record = String::new();
+            for line in lines.by_ref() {
+                let (line_no, line) = match line {
+                    Ok(line) => line,
+                    Err(e) => return Some(Err(e)),
+                };
+                match (start, line.trim().is_empty()) {
+                    (None, true) => continue,
+                    (Some(_), true) => break,
+                    (None, false) => start = Some(line_no),
+                    (Some(_), false) => record.push('\n'),
+                }
+                record.push_str(&line);
+            }
+
+            let start = start?;
+            Some(
+                record
+                    .parse()
+                    .map(|value| (start, value))
+                    .map_err(|e| anyhow!("failed to parse record at line {start}: {e:?}")),
+            )
+        }))
+    }
+
+    pub fn safe_records<T>(self) -> impl Iterator<Item = (usize, T)>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Debug,
+    {
+        self.records().unwrap().map(Result::unwrap)
+    }
+
+    fn into_reader(self) -> crate::Result<Box<dyn BufRead>> {
+        match (self.data, self.file) {
+            (Some(data), _) => Ok(Box::new(Cursor::new(data))),
+            (None, Some(path)) => Ok(Box::new(BufReader::new(
+                File::open(&path).with_context(|| format!("failed to open {}", path.display()))?,
+            ))),
+            (None, None) => Err(anyhow!("data not set")),
+        }
+    }
+
+    fn numbered_lines<R>(reader: R) -> impl Iterator<Item = crate::Result<(usize, String)>>
+    where
+        R: BufRead,
+    {
+        reader.lines().enumerate().map(|(i, line)| {
+            let line_no = i + 1;
+            line.map(|line| (line_no, line))
+                .with_context(|| format!("failed to read line {line_no}"))
+        })
+    }
+
+    fn grid_label(line: &str) -> Option<&str> {
+        line.trim_end()
+            .strip_suffix(':')
+            .filter(|label| !label.is_empty() && !label.contains(':'))
+    }
+
     fn parse_many<T, L, S>(lines: L) -> crate::Result<Vec<T>>
     where
         T: FromStr,
@@ -275,16 +584,74 @@ impl<'a> Input<'a> {
             })
             .collect()
     }
+
+    fn parse_canvas<T, L, S, F>(lines: L, mut f: F) -> crate::Result<Canvas<T>>
+    where
+        L: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        F: FnMut(Pt, char) -> T,
+    {
+        let rows: Vec<Vec<T>> = lines
+            .into_iter()
+            .enumerate()
+            .map(|(y, line)| {
+                line.as_ref()
+                    .chars()
+                    .enumerate()
+                    .map(|(x, c)| f(Pt::new(x as i64, y as i64), c))
+                    .collect()
+            })
+            .collect();
+
+        if let Some(row) = rows.first()
+            && let Some((y, _)) = rows.iter().find_position(|r| r.len() != row.len())
+        {
+            return Err(anyhow!(
+                "all rows need to have the same width (row {y} has a different width than row 0)"
+            ));
+        }
+
+        Ok(Canvas::from_matrix(rows))
+    }
 }
 
 impl<'a> TryFrom<Input<'a>> for String {
     type Error = crate::Error;
 
     fn try_from(input: Input<'a>) -> Result<Self, Self::Error> {
-        input.data.ok_or_else(|| anyhow!("data not set"))
+        match (input.data, input.file) {
+            (Some(data), _) => Ok(data),
+            (None, Some(path)) => read_to_string(&path)
+                .map(strip_trailing_newline)
+                .with_context(|| format!("failed to read {}", path.display())),
+            (None, None) => Err(anyhow!("data not set")),
+        }
     }
 }
 
+const USER_AGENT: &str = concat!("aoclp/", env!("CARGO_PKG_VERSION"));
+
+/// Returns the `.aocf` directory used by the `aocf` CLI to store the session cookie and
+/// cached inputs, either in the project root found by `aocf` or in the current directory.
+fn aocf_dir() -> PathBuf {
+    aocf::find_root().unwrap_or_default().join(".aocf")
+}
+
+/// Reads the session cookie saved by `aocf set-cookie`.
+fn aocf_session() -> crate::Result<String> {
+    let path = aocf_dir().join("cookie");
+    read_to_string(&path)
+        .map(|cookie| cookie.trim().to_string())
+        .with_context(|| format!("session not set and no cookie found in {}", path.display()))
+}
+
+fn strip_trailing_newline(mut data: String) -> String {
+    if data.ends_with('\n') {
+        data.remove(data.len() - 1);
+    }
+    data
+}
+
 pub fn safe_get_input(year: i32, day: u32) -> String {
     Input::year(year).day(day).safe_get().safe_into()
 }
@@ -364,3 +731,299 @@ where
 {
     Input::year(year).day(day).safe_get().safe_into_terrain()
 }
+
+pub fn safe_get_input_as_canvas<T>(year: i32, day: u32) -> Canvas<T>
+where
+    T: From<char>,
+{
+    Input::year(year).day(day).safe_get().safe_into_canvas()
+}
+
+pub fn safe_get_input_as_grid_with_markers<T>(
+    year: i32,
+    day: u32,
+    markers: &[char],
+    default: T,
+) -> (Canvas<T>, HashMap<char, Vec<Pt>>)
+where
+    T: From<char> + Clone,
+{
+    Input::year(year)
+        .day(day)
+        .safe_get()
+        .safe_into_grid_with_markers(markers, default)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    use super::*;
+
+    const SESSION: &str = "c0ffee";
+
+    /// Starts a stand-in for the Advent of Code website on `127.0.0.1` and returns its
+    /// base URL, along with a counter of the number of requests it received.
+    fn stand_in_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+
+        let server_hits = Arc::clone(&hits);
+        thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                server_hits.fetch_add(1, Ordering::SeqCst);
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let authorized = request
+                    .lines()
+                    .any(|line| line.eq_ignore_ascii_case(&format!("cookie: session={SESSION}")));
+
+                let (status, body) = match (authorized, path) {
+                    (false, _) => ("400 Bad Request", "Puzzle inputs differ by user.\n"),
+                    (true, "/2017/day/1/input") => ("200 OK", "1122\n"),
+                    (true, "/2017/day/2/input") => ("200 OK", "5 1 9 5\n7 5 3\n"),
+                    (true, _) => ("404 Not Found", "404 Not Found\n"),
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+
+        (base_url, hits)
+    }
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoclp-cache-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    const GRID: &str = "#S.\n.#.\n..E";
+
+    #[test]
+    fn test_into_canvas() {
+        let canvas: Canvas<char> = Input::for_example(GRID).safe_into_canvas();
+        assert_eq!(3, canvas.width());
+        assert_eq!(3, canvas.height());
+        assert_eq!('S', canvas[(1, 0)]);
+        assert_eq!('E', canvas[(2, 2)]);
+
+        assert!(Input::for_example("..\n...").into_canvas::<char>().is_err());
+    }
+
+    #[test]
+    fn test_into_grid_with_markers() {
+        let (canvas, markers) =
+            Input::for_example(GRID).safe_into_grid_with_markers(&['S', 'E'], '.');
+        assert_eq!(Canvas::from_lines(["#..", ".#.", "..."], |c| c), canvas);
+        assert_eq!(vec![Pt::new(1, 0)], markers[&'S']);
+        assert_eq!(vec![Pt::new(2, 2)], markers[&'E']);
+    }
+
+    #[test]
+    fn test_into_labelled_canvases() {
+        use crate::mapping::canvas::fixed;
+
+        let input = "0:\n#.\n.#\n\n1:\n##\n#.\n\n4x4: 0 1\n2x2: 1 0";
+
+        let (canvases, rest): (Vec<(_, fixed::Canvas<bool, 2>)>, _) =
+            Input::for_example(input).safe_into_labelled_canvases(|c| c == '#');
+        assert_eq!(
+            vec![
+                ("0".to_string(), fixed::Canvas::from_array_matrix([[true, false], [false, true]])),
+                ("1".to_string(), fixed::Canvas::from_array_matrix([[true, true], [true, false]])),
+            ],
+            canvases
+        );
+        assert_eq!("4x4: 0 1\n2x2: 1 0", rest);
+
+        let (canvases, rest): (Vec<(_, Canvas<char>)>, _) =
+            Input::for_example("Tile 7:\n.#.\n").safe_into_labelled_canvases(|c| c);
+        assert_eq!(vec![("Tile 7".to_string(), Canvas::from_lines([".#."], |c| c))], canvases);
+        assert!(rest.is_empty());
+
+        assert!(
+            Input::for_example(input)
+                .into_labelled_canvases::<fixed::Canvas<bool, 3>, _, _>(|c| c == '#')
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_into_columns() {
+        let input = "123 328  51 64\n 45 64  387 23\n  6 98  215 314\n*   +   *   +";
+
+        let blocks = Input::for_example(input).safe_into_columns();
+        assert_eq!(4, blocks.len());
+        assert_eq!(
+            ColumnBlock {
+                start: 0,
+                rows: vec!["123".into(), " 45".into(), "  6".into(), "*  ".into()],
+                columns: vec!["1  *".into(), "24  ".into(), "356 ".into()],
+            },
+            blocks[0]
+        );
+        assert_eq!(8, blocks[2].start);
+        assert_eq!(vec![" 51", "387", "215", "*  "], blocks[2].rows);
+        assert_eq!(vec!["64 ", "23 ", "314", "+  "], blocks[3].rows);
+        assert_eq!(vec!["623+", "431 ", "  4 "], blocks[3].columns);
+    }
+
+    #[test]
+    fn test_lines_parsed() {
+        let mut lines = Input::for_example("1\n2\nthree\n4")
+            .lines_parsed::<u32>()
+            .unwrap();
+        assert_eq!((1, 1), lines.next().unwrap().unwrap());
+        assert_eq!((2, 2), lines.next().unwrap().unwrap());
+        let err = lines.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("at line 3"));
+        assert_eq!((4, 4), lines.next().unwrap().unwrap());
+        assert!(lines.next().is_none());
+
+        let sum = Input::for_example("1\n2\n3")
+            .safe_lines_parsed::<u32>()
+            .fold(0, |acc, (_, n)| acc + n);
+        assert_eq!(6, sum);
+    }
+
+    #[test]
+    fn test_records() {
+        let records = Input::for_example("\na\nb\n\n\nc\n\nd\ne\nf\n")
+            .safe_records::<String>()
+            .collect_vec();
+        assert_eq!(
+            vec![(2, "a\nb".to_string()), (6, "c".to_string()), (8, "d\ne\nf".to_string())],
+            records
+        );
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("aoclp-input-{}.txt", std::process::id()));
+        std::fs::write(&path, "10\n20\n\n30\n").unwrap();
+
+        let lines = Input::from_file(&path)
+            .safe_lines_parsed::<String>()
+            .collect_vec();
+        assert_eq!(
+            vec![
+                (1, "10".to_string()),
+                (2, "20".to_string()),
+                (3, "".to_string()),
+                (4, "30".to_string())
+            ],
+            lines
+        );
+        let records = Input::from_file(&path)
+            .safe_records::<String>()
+            .collect_vec();
+        assert_eq!(vec![(1, "10\n20".to_string()), (4, "30".to_string())], records);
+        assert_eq!(
+            vec![vec![10], vec![20]],
+            Input::from_file(&path).safe_into_many_vecs::<u32>()[..2]
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(Input::from_file(&path).lines_parsed::<String>().is_err());
+    }
+
+    #[test]
+    fn test_get_from_server() {
+        let (base_url, hits) = stand_in_server();
+        let cache_dir = temp_cache_dir("server");
+
+        let input = Input::year(2017)
+            .day(1)
+            .base_url(&base_url)
+            .session(SESSION)
+            .cache_dir(&cache_dir);
+        assert_eq!("1122", input.safe_get().safe_into::<String>());
+
+        let input = Input::year(2017)
+            .day(2)
+            .base_url(format!("{base_url}/"))
+            .session(SESSION)
+            .cache_dir(&cache_dir);
+        assert_eq!(
+            vec![vec![5, 1, 9, 5], vec![7, 5, 3]],
+            input.safe_get().safe_into_many_vecs::<u32>()
+        );
+        assert_eq!(2, hits.load(Ordering::SeqCst));
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_with_cache() {
+        let (base_url, hits) = stand_in_server();
+        let cache_dir = temp_cache_dir("get");
+        let input = || {
+            Input::year(2017)
+                .day(1)
+                .base_url(&base_url)
+                .session(SESSION)
+                .cache_dir(&cache_dir)
+        };
+
+        assert_eq!("1122", input().safe_get().safe_into::<String>());
+        assert_eq!(1, hits.load(Ordering::SeqCst));
+        assert_eq!("1122\n", read_to_string(cache_dir.join("2017").join("day_01.txt")).unwrap());
+
+        assert_eq!("1122", input().safe_get().safe_into::<String>());
+        assert_eq!(1, hits.load(Ordering::SeqCst));
+
+        assert_eq!("1122", input().force(true).safe_get().safe_into::<String>());
+        assert_eq!(2, hits.load(Ordering::SeqCst));
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_errors() {
+        let (base_url, hits) = stand_in_server();
+        let cache_dir = temp_cache_dir("errors");
+        let input = |day: Option<u32>, session: &str| {
+            let input = Input::year(2017)
+                .base_url(&base_url)
+                .session(session)
+                .cache_dir(&cache_dir);
+            match day {
+                Some(day) => input.day(day),
+                None => input,
+            }
+        };
+
+        let err = input(None, SESSION).get().unwrap_err();
+        assert_eq!("day not set", err.to_string());
+        assert_eq!(0, hits.load(Ordering::SeqCst));
+
+        let err = input(Some(25), SESSION).get().unwrap_err();
+        assert_eq!("input not found for year 2017 day 25", err.to_string());
+
+        let err = input(Some(1), "bad").get().unwrap_err();
+        assert_eq!(
+            format!("session rejected while fetching {base_url}/2017/day/1/input"),
+            err.to_string()
+        );
+        assert!(!cache_dir.exists());
+        assert_eq!(2, hits.load(Ordering::SeqCst));
+    }
+}
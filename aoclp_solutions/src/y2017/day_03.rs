@@ -1,12 +1,12 @@
-use std::collections::HashMap;
 use std::iter;
 
-use aoclp::num::{Zero, zero};
+use aoclp::mapping::grid::Neighbourhood;
+use aoclp::mapping::sparse::SparseGrid;
+use aoclp::num::zero;
 use aoclp::positioning::direction::four_points::Direction4;
 use aoclp::positioning::direction::{Direction, MovementDirection};
 use aoclp::positioning::pt::{Pt, manhattan};
 use aoclp::solvers_impl::input::safe_get_input;
-use itertools::Itertools;
 
 pub fn part_1() -> i64 {
     manhattan(zero(), spiral().nth(input() - 1).unwrap())
@@ -44,22 +44,14 @@ fn spiral() -> impl Iterator<Item = Pt> {
 }
 
 fn spiral_stress_test() -> impl Iterator<Item = i64> {
-    let mut values = HashMap::new();
-    let around: Vec<_> = (-1_i64..=1)
-        .cartesian_product(-1_i64..=1)
-        .map_into::<Pt>()
-        .filter(|pt| !pt.is_zero())
-        .collect();
+    let mut values = SparseGrid::new(0_i64);
 
     spiral().map(move |pt| {
-        let value = around
-            .iter()
-            .filter_map(|pt_mod| {
-                let neighbour = pt + *pt_mod;
-                values.get(&neighbour).copied()
-            })
-            .sum1()
-            .unwrap_or(1_i64);
+        let value = values
+            .neighbours(pt, Neighbourhood::Eight)
+            .map(|(_, value)| *value)
+            .sum::<i64>()
+            .max(1);
         values.insert(pt, value);
         value
     })
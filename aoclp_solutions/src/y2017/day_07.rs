@@ -1,11 +1,12 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::OnceLock;
 
 use aoclp::captures::CapturesHelper;
+use aoclp::mapping::graph::DiGraph;
 use aoclp::regex::Regex;
 use aoclp::solvers_impl::input::safe_get_input_as_many;
 use itertools::Itertools;
@@ -106,31 +107,37 @@ struct Tower {
 
 impl Tower {
     fn build() -> Self {
-        let mut programs = HashMap::new();
-        let mut parents = HashSet::new();
+        let specs = input();
+        let bottom_name = DiGraph::from_adjacency(
+            specs
+                .iter()
+                .map(|spec| (spec.name.clone(), spec.sub_programs.clone())),
+        )
+        .toposort()
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
 
-        for spec in input() {
-            let program = Rc::clone(programs.entry(spec.name.clone()).or_insert_with(|| {
-                parents.insert(spec.name.clone());
-                Program::new(spec.name)
-            }));
+        let mut programs = HashMap::new();
+        for spec in specs {
+            let program = Rc::clone(
+                programs
+                    .entry(spec.name.clone())
+                    .or_insert_with(|| Program::new(spec.name)),
+            );
             program.borrow_mut().weight = spec.weight;
             for sub_prog_name in spec.sub_programs {
                 let sub_program = Rc::clone(
                     programs
                         .entry(sub_prog_name.clone())
-                        .or_insert_with(|| Program::new(sub_prog_name.clone())),
+                        .or_insert_with(|| Program::new(sub_prog_name)),
                 );
                 program.borrow_mut().sub_programs.push(sub_program);
-                parents.remove(&sub_prog_name);
             }
         }
 
-        Tower {
-            bottom: programs
-                .remove(&parents.into_iter().exactly_one().unwrap())
-                .unwrap(),
-        }
+        Tower { bottom: programs.remove(&bottom_name).unwrap() }
     }
 
     fn total_weights(&self) -> HashMap<String, usize> {
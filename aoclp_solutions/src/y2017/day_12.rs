@@ -1,6 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::str::FromStr;
 
+use aoclp::mapping::graph::DiGraph;
 use aoclp::solvers_impl::input::safe_get_input_as_many;
 use itertools::Itertools;
 
@@ -34,44 +35,21 @@ impl FromStr for Program {
 }
 
 #[derive(Debug)]
-struct Village(HashMap<usize, Program>);
+struct Village(DiGraph<usize>);
 
 impl Village {
     pub fn group(&self, id: usize) -> HashSet<usize> {
-        let mut group = HashSet::new();
-        self.fill_group(id, &mut group);
-        group
+        self.0.reachable_from(&id)
     }
 
     pub fn group_count(&self) -> usize {
-        let mut seen = HashSet::new();
-        let mut count = 0;
-
-        self.0.keys().for_each(|id| {
-            if !seen.contains(id) {
-                seen.extend(self.group(*id));
-                count += 1;
-            }
-        });
-
-        count
-    }
-
-    fn fill_group(&self, id: usize, group: &mut HashSet<usize>) {
-        if !group.contains(&id) {
-            let program = &self.0[&id];
-            group.insert(program.id);
-            program
-                .neighbours
-                .iter()
-                .for_each(|neighbour_id| self.fill_group(*neighbour_id, group));
-        }
+        self.0.weakly_connected_components().len()
     }
 }
 
 impl From<Vec<Program>> for Village {
     fn from(value: Vec<Program>) -> Self {
-        Self(value.into_iter().map(|p| (p.id, p)).collect())
+        Self(DiGraph::from_adjacency(value.into_iter().map(|p| (p.id, p.neighbours))))
     }
 }
 
@@ -1,3 +1,5 @@
+use aoclp::mapping::canvas::dynamic::Canvas;
+use aoclp::mapping::sparse::SparseGrid;
 use aoclp::positioning::direction::four_points::Direction4;
 use aoclp::positioning::pt::Pt;
 use aoclp::positioning::turtle::Turtle;
@@ -40,57 +42,29 @@ impl NodeState {
     }
 }
 
+/// The infinite grid of nodes; nodes we haven't visited yet are clean.
 #[derive(Debug)]
-struct Cluster(Vec<NodeState>);
+struct Cluster(SparseGrid<NodeState>);
 
 impl Cluster {
-    pub fn new() -> Self {
-        let mut nodes = Vec::new();
-        nodes.resize(1_000 * 1_000, NodeState::Clean);
-        Self(nodes)
-    }
-
     pub fn get_state(&self, node: &Node) -> NodeState {
-        self.0
-            .get(Self::index(node))
-            .copied()
-            .unwrap_or(NodeState::Clean)
+        *self.0.get(*node)
     }
 
     pub fn modify_state(&mut self, node: &Node, evolved: bool) -> NodeState {
-        let next_state = self.get_state(node).next(evolved);
-        self.set_state(node, next_state);
-        next_state
-    }
-
-    fn index(node: &Node) -> usize {
-        ((node.x + 500) * 1_000 + node.y + 500) as usize
-    }
-
-    fn set_state(&mut self, node: &Node, state: NodeState) {
-        let state_ref = self
-            .0
-            .get_mut(Self::index(node))
-            .expect("cluster size is not big enough for puzzle");
-        *state_ref = state;
+        let state = self.0.get_mut(*node);
+        *state = state.next(evolved);
+        *state
     }
 }
 
 impl From<Vec<Vec<char>>> for Cluster {
     fn from(value: Vec<Vec<char>>) -> Self {
-        let mut cluster = Self::new();
-
-        value.into_iter().enumerate().for_each(|(y, row)| {
-            row.into_iter()
-                .enumerate()
-                .filter(|(_, node)| *node == '#')
-                .for_each(|(x, _)| {
-                    let node = Node::new(x as i64, y as i64);
-                    cluster.set_state(&node, NodeState::Infected);
-                });
+        let nodes = Canvas::from_matrix(value).map(|node| match node {
+            '#' => NodeState::Infected,
+            _ => NodeState::Clean,
         });
-
-        cluster
+        Self(SparseGrid::from_canvas(nodes, NodeState::Clean))
     }
 }
 
@@ -1,61 +1,84 @@
 use std::convert::Infallible;
 use std::str::FromStr;
 
+use aoclp::search::{StateSpace, branch_and_bound};
 use aoclp::solvers_impl::input::safe_get_input_as_many;
 use itertools::Itertools;
 
 pub fn part_1() -> usize {
-    bridges(vec![], initial_parts())
-        .into_iter()
-        .map(bridge_strength)
-        .max()
-        .unwrap()
+    best_bridge(|_, strength| strength)
 }
 
 pub fn part_2() -> usize {
-    bridges(vec![], initial_parts())
-        .into_iter()
-        .max_by(|b1, b2| {
-            b1.len()
-                .cmp(&b2.len())
-                .then_with(|| bridge_strength(b1).cmp(&bridge_strength(b2)))
-        })
-        .map(bridge_strength)
+    best_bridge(|length, strength| (length, strength)).1
+}
+
+fn best_bridge<S, F>(score: F) -> S
+where
+    S: Copy + Ord,
+    F: Fn(usize, usize) -> S,
+{
+    let bridges = Bridges { parts: initial_parts(), score };
+    branch_and_bound(&bridges, Bridge::default())
+        .best_score()
         .unwrap()
 }
 
-fn bridges(bridge: Vec<Part>, parts: Vec<Part>) -> Vec<Vec<Part>> {
-    let connect_to = bridge.last().map(|part| part.1).unwrap_or_default();
-    let candidates = parts
-        .iter()
-        .enumerate()
-        .filter_map(|(i, part)| {
-            part.connecting(connect_to)
-                .map(|connecting_part| (i, connecting_part))
-        })
-        .collect_vec();
-    if candidates.is_empty() {
-        return vec![bridge];
-    }
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+struct Bridge {
+    used: u128,
+    port: usize,
+    length: usize,
+    strength: usize,
+}
 
-    candidates
-        .into_iter()
-        .flat_map(|(i, part)| {
-            let mut new_bridge = bridge.clone();
-            new_bridge.push(part);
-            let mut new_parts = parts.clone();
-            new_parts.remove(i);
+struct Bridges<F> {
+    parts: Vec<Part>,
+    score: F,
+}
 
-            bridges(new_bridge, new_parts)
-        })
-        .collect_vec()
+impl<F> Bridges<F> {
+    fn unused<'a>(
+        &'a self,
+        bridge: &Bridge,
+    ) -> impl Iterator<Item = (usize, &'a Part)> + use<'a, F> {
+        let used = bridge.used;
+        self.parts
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| used & (1 << i) == 0)
+    }
 }
 
-fn bridge_strength<P>(parts: P) -> usize
+impl<S, F> StateSpace for Bridges<F>
 where
-    P: AsRef<[Part]>,
+    S: Copy + Ord,
+    F: Fn(usize, usize) -> S,
 {
-    parts.as_ref().iter().map(Part::strength).sum()
+    type State = Bridge;
+    type Score = S;
+
+    fn successors(&self, bridge: &Bridge) -> impl Iterator<Item = Bridge> {
+        self.unused(bridge).filter_map(|(i, part)| {
+            part.connecting(bridge.port).map(|part| Bridge {
+                used: bridge.used | (1 << i),
+                port: part.1,
+                length: bridge.length + 1,
+                strength: bridge.strength + part.strength(),
+            })
+        })
+    }
+
+    fn score(&self, bridge: &Bridge) -> S {
+        (self.score)(bridge.length, bridge.strength)
+    }
+
+    fn bound(&self, bridge: &Bridge) -> Option<S> {
+        let (count, strength) = self
+            .unused(bridge)
+            .fold((0, 0), |(count, strength), (_, part)| (count + 1, strength + part.strength()));
+        Some((self.score)(bridge.length + count, bridge.strength + strength))
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -95,5 +118,7 @@ impl FromStr for Part {
 }
 
 fn initial_parts() -> Vec<Part> {
-    safe_get_input_as_many(2017, 24)
+    let parts: Vec<Part> = safe_get_input_as_many(2017, 24);
+    assert!(parts.len() <= 128, "too many parts: {}", parts.len());
+    parts
 }
@@ -1,5 +1,4 @@
-use std::cmp::Ordering;
-
+use aoclp::mapping::graph::DiGraph;
 use aoclp::solvers_impl::input::safe_get_input_as_many_vecs_of_two_types;
 use itertools::Itertools;
 
@@ -30,17 +29,9 @@ fn input() -> (Vec<Rule>, Vec<Update>) {
 struct Rule(u64, u64);
 
 impl Rule {
-    pub fn first(&self) -> u64 {
-        self.0
-    }
-
     pub fn positions_in(&self, v: &[u64]) -> (Option<usize>, Option<usize>) {
         (v.iter().position(|n| *n == self.0), v.iter().position(|n| *n == self.1))
     }
-
-    pub fn applies_to(&self, page_1: u64, page_2: u64) -> bool {
-        (self.0 == page_1 && self.1 == page_2) || (self.0 == page_2 && self.1 == page_1)
-    }
 }
 
 impl From<Vec<u64>> for Rule {
@@ -66,18 +57,14 @@ impl Update {
     }
 
     pub fn try_fix(&self, rules: &[Rule]) -> Option<Self> {
-        let fixed = self
-            .0
-            .iter()
-            .copied()
-            .sorted_by(|page_1, page_2| {
-                match rules.iter().find(|rule| rule.applies_to(*page_1, *page_2)) {
-                    Some(rule) if rule.first() == *page_1 => Ordering::Less,
-                    Some(_) => Ordering::Greater,
-                    _ => Ordering::Equal,
-                }
-            })
-            .collect_vec();
+        let mut graph = DiGraph::from_edges(
+            rules
+                .iter()
+                .filter(|rule| self.0.contains(&rule.0) && self.0.contains(&rule.1))
+                .map(|rule| (rule.0, rule.1)),
+        );
+        self.0.iter().for_each(|page| graph.add_node(*page));
+        let fixed = graph.toposort().unwrap();
 
         (fixed != self.0).then_some(Self(fixed))
     }
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
 use aoclp::mapping::dij;
+use aoclp::mapping::grid::{GridGraph, Neighbourhood};
+use aoclp::num::ToPrimitive;
 use aoclp::positioning::pt::{Pt, matrix_to_map};
 use aoclp::solvers_impl::input::safe_get_input_as_terrain;
+use itertools::Itertools;
 
 pub fn part_1() -> usize {
     Map::default().trailheads().map(|h| h.score).sum()
@@ -41,52 +44,33 @@ impl Map {
             .iter()
             .filter(|(_, t)| **t == 0)
             .filter_map(move |(p, _)| {
-                let dist = dij::build(self, *p).dist;
-                let score = dist.values().filter(|d| **d == 9).count();
-                if score == 0 {
+                let output = dij::build_all(&self.graph(), *p);
+                let summits = output
+                    .dist
+                    .iter()
+                    .filter(|(_, d)| **d == 9)
+                    .map(|(summit, _)| *summit)
+                    .collect_vec();
+                if summits.is_empty() {
                     return None;
                 }
 
-                let mut cache = HashMap::new();
                 let trailhead = Trailhead {
                     start: *p,
-                    score,
-                    rating: dist
+                    score: summits.len(),
+                    rating: summits
                         .iter()
-                        .filter(|(_, d)| **d == 9)
-                        .map(|(p, _)| Self::path_count(*p, &dist, &mut cache))
+                        .map(|summit| output.path_count(p, summit).to_usize().unwrap())
                         .sum(),
                 };
                 Some(trailhead)
             })
     }
 
-    fn path_count(p: Pt, dist: &HashMap<Pt, usize>, cache: &mut HashMap<Pt, usize>) -> usize {
-        if let Some(count) = cache.get(&p) {
-            return *count;
-        }
-
-        let p_dist = dist[&p];
-        if p_dist == 0 {
-            return 1;
-        }
-
-        let count = p
-            .four_neighbours()
-            .filter(|n| dist.get(n).is_some_and(|d| *d == p_dist - 1))
-            .map(|n| Self::path_count(n, dist, cache))
-            .sum();
-        cache.insert(p, count);
-
-        count
-    }
-}
-
-impl dij::Graph<Pt> for Map {
-    fn neighbours(&self, node: &Pt) -> impl Iterator<Item = Pt> {
-        let height = self.heightmap[node];
-        node.four_neighbours()
-            .filter(move |n| self.heightmap.get(n).is_some_and(|h| *h == height + 1))
+    fn graph(&self) -> GridGraph<'_, HashMap<Pt, usize>, impl Fn(&usize, &usize) -> bool> {
+        GridGraph::new(&self.heightmap, Neighbourhood::Four, |from: &usize, to: &usize| {
+            *to == from + 1
+        })
     }
 }
 
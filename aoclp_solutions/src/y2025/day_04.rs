@@ -1,42 +1,33 @@
-use std::collections::{BTreeSet, HashSet};
-
-use aoclp::positioning::pt::{Pt, filtered_matrix_to_map};
-use aoclp::solvers_impl::input::safe_get_input_as_terrain;
+use aoclp::mapping::bitgrid::BitGrid;
+use aoclp::mapping::grid::Neighbourhood;
+use aoclp::solvers_impl::input::safe_get_input_as_canvas;
 
 pub fn part_1() -> usize {
-    let rolls: HashSet<Pt> = filtered_matrix_to_map(input(), |_, c| *c != '.')
-        .into_keys()
-        .collect();
-
-    rolls
-        .iter()
-        .filter(|p| p.eight_neighbours().filter(|n| rolls.contains(n)).count() < 4)
-        .count()
+    let rolls = rolls();
+    removables(&rolls).count_ones()
 }
 
 pub fn part_2() -> usize {
-    let mut rolls: BTreeSet<Pt> = filtered_matrix_to_map(input(), |_, c| *c != '.')
-        .into_keys()
-        .collect();
+    let mut rolls = rolls();
 
     let mut removed = 0;
     loop {
-        let removables: BTreeSet<_> = rolls
-            .iter()
-            .filter(|p| p.eight_neighbours().filter(|n| rolls.contains(n)).count() < 4)
-            .copied()
-            .collect();
-        if removables.is_empty() {
+        let removables = removables(&rolls);
+        if !removables.any() {
             break;
         }
 
-        removed += removables.len();
-        rolls = rolls.difference(&removables).copied().collect();
+        removed += removables.count_ones();
+        rolls ^= &removables;
     }
 
     removed
 }
 
-fn input() -> Vec<Vec<char>> {
-    safe_get_input_as_terrain(2025, 4)
+fn removables(rolls: &BitGrid) -> BitGrid {
+    rolls & &rolls.neighbour_counts(Neighbourhood::Eight).less_than(4)
+}
+
+fn rolls() -> BitGrid {
+    BitGrid::from(&safe_get_input_as_canvas::<char>(2025, 4).map(|c| c != '.'))
 }
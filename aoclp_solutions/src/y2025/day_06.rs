@@ -1,5 +1,5 @@
 use aoclp::forth::Forth;
-use aoclp::solvers_impl::input::safe_get_input;
+use aoclp::solvers_impl::input::{ColumnBlock, Input};
 use itertools::Itertools;
 
 pub fn part_1() -> usize {
@@ -30,84 +30,39 @@ impl Problem {
 }
 
 fn problems() -> Vec<Problem> {
-    let input = input();
-    let operands_list = input
-        .lines()
-        .dropping_back(1)
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|n| n.parse::<usize>().unwrap())
-                .collect_vec()
-        })
-        .collect_vec();
-
-    parse_operators(&input)
-        .into_iter()
-        .enumerate()
-        .map(|(i, operator)| {
-            let operands = operands_list
-                .iter()
-                .map(|operands| operands[i])
-                .collect_vec();
-            Problem { operands, operator }
-        })
-        .collect_vec()
+    parse_problems(|block| {
+        block
+            .rows
+            .iter()
+            .dropping_back(1)
+            .map(|row| row.trim_ascii().parse().unwrap())
+            .collect()
+    })
 }
 
 fn cephaloproblems() -> Vec<Problem> {
-    let input = input();
-    let operators = parse_operators(&input);
-    let mut operands = Vec::new();
-
-    let operand_lines = input.lines().dropping_back(1).collect_vec();
-    let max_len = operand_lines.iter().map(|line| line.len()).max().unwrap();
-    let mut add_problem_operands = |first_col, col| {
-        let mut problem_operands = Vec::new();
-        for problem_col in first_col..col {
-            let n: usize = operand_lines
-                .iter()
-                .map(|line| line.chars().nth(problem_col).unwrap())
-                .join("")
-                .trim_ascii()
-                .parse()
-                .unwrap();
-            problem_operands.push(n);
-        }
-        operands.push(problem_operands);
-    };
-
-    let mut first_col = 0;
-    for col in 0..max_len {
-        if operand_lines
+    parse_problems(|block| {
+        block
+            .columns
             .iter()
-            .map(|line| line.chars().nth(col).unwrap())
-            .all(|c| c == ' ')
-        {
-            add_problem_operands(first_col, col);
-            first_col = col + 1;
-        }
-    }
-    if first_col != max_len {
-        add_problem_operands(first_col, max_len);
-    }
-
-    operands
-        .into_iter()
-        .zip(operators)
-        .map(|(operands, operator)| Problem { operands, operator })
-        .collect_vec()
+            .map(|col| col[..col.len() - 1].trim_ascii().parse().unwrap())
+            .collect()
+    })
 }
 
-fn parse_operators(input: &str) -> Vec<String> {
-    input
-        .lines()
-        .last()
-        .unwrap()
-        .split_ascii_whitespace()
-        .map(<_>::to_string)
+fn parse_problems<F>(mut operands: F) -> Vec<Problem>
+where
+    F: FnMut(&ColumnBlock) -> Vec<usize>,
+{
+    input()
+        .into_iter()
+        .map(|block| {
+            let operator = block.rows.last().unwrap().trim_ascii().to_string();
+            Problem { operands: operands(&block), operator }
+        })
         .collect_vec()
 }
 
-fn input() -> String {
-    safe_get_input(2025, 6)
+fn input() -> Vec<ColumnBlock> {
+    Input::year(2025).day(6).safe_get().safe_into_columns()
 }
@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
 use aoclp::positioning::direction::four_points::Direction4;
-use aoclp::positioning::pt::{Pt, matrix_to_map};
-use aoclp::solvers_impl::input::safe_get_input_as_terrain;
+use aoclp::positioning::pt::Pt;
+use aoclp::solvers_impl::input::safe_get_input_as_grid_with_markers;
 use derive_where::derive_where;
 use itertools::Itertools;
 
@@ -22,9 +22,8 @@ struct Manifold {
 
 impl Default for Manifold {
     fn default() -> Self {
-        let parts = matrix_to_map(input());
-        let starting_point = *parts.iter().find(|(_, c)| **c == 'S').unwrap().0;
-        Self { parts, starting_point }
+        let (canvas, markers) = safe_get_input_as_grid_with_markers(2025, 7, &['S'], '.');
+        Self { parts: canvas.into_map(), starting_point: markers[&'S'][0] }
     }
 }
 
@@ -98,7 +97,3 @@ fn manifoldize() -> ManifoldizationResult {
     let worlds = particles.into_iter().map(|p| p.worlds).sum();
     ManifoldizationResult { splits, worlds }
 }
-
-fn input() -> Vec<Vec<char>> {
-    safe_get_input_as_terrain(2025, 7)
-}
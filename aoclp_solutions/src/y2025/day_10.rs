@@ -1,8 +1,10 @@
+use std::cmp::Reverse;
 use std::str::FromStr;
 use std::sync::OnceLock;
 
 use aoclp::anyhow::Context;
 use aoclp::regex::Regex;
+use aoclp::search::{StateSpace, best_first};
 use aoclp::solvers_impl::input::{Input, safe_get_input_as_many};
 use itertools::Itertools;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
@@ -29,28 +31,23 @@ struct Machine {
 
 impl Machine {
     fn fewest_presses_for_lights(&self) -> usize {
-        let mut presses = 0;
-        let mut states = vec![vec![false; self.target_lights.len()]];
-        loop {
-            if states.iter().contains(&self.target_lights) {
-                break presses;
-            }
+        let outcome = best_first(&LightToggling(self), 0);
+        outcome.best.unwrap().0.count_ones() as usize
+    }
 
-            states = states
-                .into_iter()
-                .flat_map(|l| {
-                    self.button_wirings.iter().map(move |wiring| {
-                        let mut next = l.clone();
-                        for w in wiring {
-                            next[*w] = !next[*w]
-                        }
-                        next
-                    })
-                })
-                .unique()
-                .collect();
-            presses += 1;
+    fn lights_after(&self, pressed: u64) -> Vec<bool> {
+        let mut lights = vec![false; self.target_lights.len()];
+        for (_, wiring) in self
+            .button_wirings
+            .iter()
+            .enumerate()
+            .filter(|(b, _)| pressed & (1 << b) != 0)
+        {
+            for w in wiring {
+                lights[*w] = !lights[*w];
+            }
         }
+        lights
     }
 
     fn fewest_presses_for_joltage(&self) -> usize {
@@ -95,6 +92,29 @@ impl Machine {
     }
 }
 
+/// Search for the button presses that turn on the target lights. Since pressing a button
+/// twice cancels out, states are bitmasks of the buttons pressed so far.
+struct LightToggling<'a>(&'a Machine);
+
+impl StateSpace for LightToggling<'_> {
+    type State = u64;
+    type Score = Reverse<u32>;
+
+    fn successors(&self, pressed: &u64) -> impl Iterator<Item = u64> {
+        let pressed = *pressed;
+        let first = (u64::BITS - pressed.leading_zeros()) as usize;
+        (first..self.0.button_wirings.len()).map(move |b| pressed | (1 << b))
+    }
+
+    fn score(&self, pressed: &u64) -> Reverse<u32> {
+        Reverse(pressed.count_ones())
+    }
+
+    fn is_goal(&self, pressed: &u64) -> bool {
+        self.0.lights_after(*pressed) == self.0.target_lights
+    }
+}
+
 impl FromStr for Machine {
     type Err = aoclp::Error;
 
@@ -1,50 +1,29 @@
-use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::OnceLock;
 
 use aoclp::anyhow::Context;
+use aoclp::mapping::graph::DiGraph;
+use aoclp::num::ToPrimitive;
 use aoclp::regex::Regex;
 use aoclp::solvers_impl::input::safe_get_input_as_many;
 use itertools::Itertools;
 
 pub fn part_1() -> usize {
-    let devices = devices_map();
-    let mut cache = HashMap::new();
-    num_paths(&devices, "you", true, true, &mut cache)
+    num_paths("you", &[])
 }
 
 pub fn part_2() -> usize {
-    let devices = devices_map();
-    let mut cache = HashMap::new();
-    num_paths(&devices, "svr", false, false, &mut cache)
+    num_paths("svr", &["dac", "fft"])
 }
 
-fn devices_map() -> HashMap<String, Vec<String>> {
-    input().into_iter().map(|d| (d.name, d.outputs)).collect()
-}
-
-fn num_paths(
-    devices: &HashMap<String, Vec<String>>,
-    cur: &str,
-    dac: bool,
-    fft: bool,
-    cache: &mut HashMap<(String, bool, bool), usize>,
-) -> usize {
-    if let Some(num) = cache.get(&(cur.to_string(), dac, fft)) {
-        return *num;
-    }
-    if cur == "out" {
-        return if dac && fft { 1 } else { 0 };
-    }
-
-    let (dac, fft) = (dac || cur == "dac", fft || cur == "fft");
-    let outputs = &devices[cur];
-    let num = outputs
-        .iter()
-        .map(|d| num_paths(devices, d, dac, fft, cache))
-        .sum();
-    cache.insert((cur.to_string(), dac, fft), num);
-    num
+fn num_paths(from: &str, waypoints: &[&str]) -> usize {
+    let devices = DiGraph::from_adjacency(input().into_iter().map(|d| (d.name, d.outputs)));
+    let waypoints = waypoints.iter().map(ToString::to_string).collect_vec();
+    devices
+        .count_paths_via(&from.into(), &"out".into(), &waypoints)
+        .unwrap()
+        .to_usize()
+        .unwrap()
 }
 
 #[derive(Debug, Clone)]
@@ -4,15 +4,18 @@ use std::sync::OnceLock;
 use aoclp::anyhow::Context;
 use aoclp::captures::CapturesHelper;
 use aoclp::mapping::canvas::fixed::Canvas;
+use aoclp::mapping::packing::Packer;
 use aoclp::regex::Regex;
-use aoclp::solvers_impl::input::safe_get_input;
+use aoclp::solvers_impl::input::Input;
 use itertools::Itertools;
 
 pub fn part_1() -> usize {
-    let input = input();
-    println!("Presents: {}, regions: {}", input.0.len(), input.1.len());
-
-    0
+    let (presents, regions) = input();
+    let packer = Packer::new(presents);
+    regions
+        .into_iter()
+        .filter(|region| packer.fits(region.width, region.height, &region.presents))
+        .count()
 }
 
 pub fn part_2() -> usize {
@@ -54,44 +57,25 @@ impl FromStr for Region {
 }
 
 fn input() -> (Vec<Present>, Vec<Region>) {
-    parse_input(safe_get_input(2025, 12).lines())
+    parse_input(Input::year(2025).day(12).safe_get())
 }
 
-fn parse_input<I, S>(input: I) -> (Vec<Present>, Vec<Region>)
-where
-    I: IntoIterator<Item = S>,
-    <I as IntoIterator>::IntoIter: Clone,
-    S: AsRef<str>,
-{
-    static INDEX_REGEX: OnceLock<Regex> = OnceLock::new();
-    let index_re = INDEX_REGEX.get_or_init(|| Regex::new(r"^(?<idx>\d+):\s*$").unwrap());
-
-    let it = input.into_iter();
-
-    let mut present_it = it
-        .clone()
-        .take_while(|line| line.as_ref().parse::<Region>().is_err());
-    let mut presents = Vec::new();
-    let mut i = 0;
-    loop {
-        match present_it.next() {
-            None => break,
-            Some(s) if s.as_ref().trim_ascii().is_empty() => continue,
-            Some(s) => {
-                let index_cap = index_re.captures(s.as_ref()).unwrap();
-                let index: usize = index_cap.ez_get("idx");
-                if index != i {
-                    panic!("expected present #{i}, found present #{index}");
-                }
-
-                let present = Present::from_lines(present_it.by_ref().take(3), |c| c == '#');
-                presents.push(present);
-                i += 1;
-            },
-        }
-    }
-
-    let regions = it.filter_map(|l| l.as_ref().parse().ok()).collect();
+fn parse_input(input: Input<'_>) -> (Vec<Present>, Vec<Region>) {
+    let (presents, regions): (Vec<(String, Present)>, String) =
+        input.safe_into_labelled_canvases(|c| c == '#');
+
+    let presents = presents
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, present))| {
+            let index: usize = label.parse().unwrap();
+            if index != i {
+                panic!("expected present #{i}, found present #{index}");
+            }
+            present
+        })
+        .collect();
+    let regions = regions.lines().filter_map(|l| l.parse().ok()).collect();
 
     (presents, regions)
 }